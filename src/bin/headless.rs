@@ -0,0 +1,115 @@
+use glamour::{Angle, Vector2};
+use palette::LinSrgb;
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::process::ExitCode;
+use std::rc::Rc;
+use sw_render::buffers::depth::DepthBuffer;
+use sw_render::buffers::image::Image;
+use sw_render::common::camera::{Camera, PerspectiveCamera};
+use sw_render::common::space::{WorldPoint, WorldVector};
+use sw_render::lights::directional_light::DirectionalLight;
+use sw_render::lights::positional_light::PositionalLight;
+use sw_render::lights::shadow_map::{ShadowFilter, ShadowMap, ShadowedLight};
+use sw_render::lights::traits::{Attenuation, LightSource};
+use sw_render::objects::mesh::Mesh;
+use sw_render::objects::object::Object;
+use sw_render::pipeline::renderer::Renderer;
+use sw_render::shaders::shading::{Material, ShadingModel, SpecularModel};
+use sw_render::textures::sampler::{Filter, Sampler, WrapMode};
+use sw_render::textures::tga::read_tga;
+
+// Same as the demo's, the head's own texture if it is there, the repo's checkerboard otherwise
+const DIFFUSE_TEXTURE_PATHS: [&str; 2] = ["african_head_diffuse.tga", "uv_checker.tga"];
+
+const USAGE: &str = "usage: headless <output.{png,bmp,tga,ppm}> [width height] [angle in degrees]";
+
+// Renders the demo scene without a window and saves it to an image file
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(output_path) = args.first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    // Zero-sized images have nothing to render into
+    let parse_dimension = |idx: usize| match args.get(idx) {
+        Some(arg) => arg.parse::<u32>().ok().filter(|&dimension| dimension > 0),
+        None => Some(480),
+    };
+    let angle = match args.get(3) {
+        Some(arg) => arg.parse::<f32>().ok(),
+        None => Some(0.0),
+    };
+    let (Some(width), Some(height), Some(angle)) = (parse_dimension(1), parse_dimension(2), angle)
+    else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let dimensions = Vector2::new(width, height);
+
+    let input = BufReader::new(File::open("african_head.obj").unwrap());
+    let mut head = Object::new(Rc::new(Mesh::from_obj(input).unwrap()));
+    head.update_world_transforms();
+
+    let mut material = Material::default();
+    if let Some(file) = DIFFUSE_TEXTURE_PATHS
+        .iter()
+        .find_map(|path| File::open(path).ok())
+    {
+        material.diffuse_texture = Some(Rc::new(read_tga(BufReader::new(file)).unwrap()));
+        material.sampler = Sampler::new(Filter::Bilinear, WrapMode::Repeat);
+    }
+
+    let (sin, cos) = Angle::from_degrees(angle).sin_cos();
+    let mut camera = PerspectiveCamera::new(
+        WorldPoint::new(3.5 * sin, 0.0, 3.5 * cos),
+        WorldVector::new(0.0, 0.0, -1.0),
+        0.1,
+        10.0,
+        37.5,
+        width as f32 / height as f32,
+    );
+    camera.look_at(&head);
+
+    let headlight = PositionalLight::new(
+        camera.position(),
+        LinSrgb::new(1.0, 1.0, 1.0),
+        0.6,
+        Attenuation::none(),
+    );
+    let key_light = DirectionalLight::new(
+        WorldVector::new(-1.0, -0.5, -1.0),
+        LinSrgb::new(1.0, 0.9, 0.75),
+        0.8,
+    );
+
+    let renderer = Renderer::new();
+    let mut shadow_map = ShadowMap::new(Vector2::new(512, 512));
+    shadow_map.filter = ShadowFilter::Pcf { radius: 1 };
+    renderer
+        .begin_shadow_pass(&mut shadow_map, &key_light, &[&head])
+        .draw_object(&head);
+    let shadowed_key_light = ShadowedLight::new(&key_light, &shadow_map);
+    let lights: [&dyn LightSource; 2] = [&headlight, &shadowed_key_light];
+
+    let mut image = Image::new(dimensions);
+    let mut depth_buffer = DepthBuffer::new(dimensions);
+    {
+        let mut frame_buffer = image.frame_buffer();
+        let mut render_pass =
+            renderer.begin_pass(&mut frame_buffer, &mut depth_buffer, &camera, &lights);
+        render_pass.draw_shaded(
+            &head,
+            ShadingModel::Phong(SpecularModel::BlinnPhong),
+            &material,
+        );
+    }
+
+    if let Err(error) = image.save(output_path) {
+        eprintln!("{error}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
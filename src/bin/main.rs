@@ -1,161 +1,367 @@
-use glamour::{Angle, Transform3, Vector2, Vector3};
-use itertools::Itertools;
-use palette::Srgb;
+use glamour::{Angle, Vector2};
+use palette::LinSrgb;
+use std::env;
 use std::fs::File;
 use std::io::BufReader;
-use std::num::NonZeroU32;
 use std::rc::Rc;
-use std::time::Instant;
-use sw_render::buffers::frame::FrameBuffer;
-use sw_render::common::camera::PerspectiveCamera;
-use sw_render::common::space::{ScreenPoint, WorldPoint, WorldSpace, WorldVector};
+use std::time::{Duration, Instant};
+use sw_render::buffers::depth::DepthBuffer;
+use sw_render::buffers::pixel_format::PixelFormat;
+use sw_render::common::camera::{Camera, PerspectiveCamera};
+use sw_render::common::space::{WorldPoint, WorldVector};
+use sw_render::common::traits::Bounded;
+use sw_render::controls::fly::FlyController;
+use sw_render::controls::orbit::OrbitController;
+use sw_render::controls::traits::CameraController;
+use sw_render::controls::winit_input::WinitInput;
+use sw_render::lights::directional_light::DirectionalLight;
+use sw_render::lights::positional_light::PositionalLight;
+use sw_render::lights::shadow_map::{ShadowFilter, ShadowMap, ShadowedLight};
+use sw_render::lights::traits::{Attenuation, LightSource};
 use sw_render::objects::mesh::Mesh;
-use winit::dpi::{LogicalSize, PhysicalSize};
-use winit::event::{Event, WindowEvent};
+use sw_render::objects::object::Object;
+use sw_render::pipeline::renderer::Renderer;
+use sw_render::present::traits::{PresentTarget, TargetFrameBuffer};
+use sw_render::present::window::WindowTarget;
+use sw_render::shaders::shading::{Material, ShadingModel, SpecularModel};
+use sw_render::textures::sampler::{Filter, Sampler, WrapMode};
+use sw_render::textures::tga::read_tga;
+use sw_render::utils::frame_clock::{FrameClock, FrameMode, Tick};
+use winit::dpi::PhysicalSize;
+use winit::event::{ElementState, Event, KeyEvent, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::{Window, WindowAttributes};
+use winit::keyboard::{Key, NamedKey};
+use winit::window::Window;
 
 const WIDTH: usize = 480;
 const HEIGHT: usize = 480;
 
-const FPS_TARGET: usize = 60;
+const FPS_TARGET: u32 = 60;
+
+// How fast the camera circles the head while left alone
+const ORBIT_SPEED_IN_DEGREES_PER_SECOND: f32 = 7.5;
+
+const STATS_INTERVAL: Duration = Duration::from_secs(5);
 
 const DISPLAY_DIMENSIONS: Vector2<u32> = Vector2::new(WIDTH as u32, HEIGHT as u32);
 
-const PIXEL_COUNT: usize = WIDTH * HEIGHT;
+// The head's own texture from tinyrenderer if it has been put next to the model, otherwise a
+// checkerboard from the repo that shows how the texture coordinates run
+const DIFFUSE_TEXTURE_PATHS: [&str; 2] = ["african_head_diffuse.tga", "uv_checker.tga"];
 
-fn transform_vertex(
-    vertex: &WorldPoint,
-    camera: &PerspectiveCamera,
-    width: usize,
-    height: usize,
-) -> ScreenPoint {
-    // Apply view matrix
-    let view_point = camera.view_matrix.map_point(*vertex);
+const USAGE: &str =
+    "usage: main [--fps <frames per second, 0 for uncapped>] [--fixed <width>x<height> | --fbdev [device]]";
 
-    // Store the view space z-coordinate
-    let view_z = view_point.z;
+enum CameraControl {
+    Orbit(OrbitController),
+    Fly(FlyController),
+}
+
+// The spinning head, independent of where its frames end up
+struct Demo {
+    clock: FrameClock,
+    last_stats: Instant,
+    input: WinitInput,
+    camera_control: CameraControl,
+    head: Object,
+    material: Material,
+    shading_model: ShadingModel,
+    headlight: PositionalLight,
+    key_light: DirectionalLight,
+    shadow_map: ShadowMap,
+    renderer: Renderer,
+    depth_buffer: DepthBuffer,
+    camera: PerspectiveCamera,
+}
 
-    // Apply perspective matrix
-    let clip_point = camera.perspective_matrix.map_point(view_point);
+impl Demo {
+    fn new(dimensions: Vector2<u32>, frame_mode: FrameMode) -> Self {
+        let input = BufReader::new(File::open("african_head.obj").unwrap());
+        let mut head = Object::new(Rc::new(Mesh::from_obj(input).unwrap()));
+        head.update_world_transforms();
 
-    // Manually calculate w
-    let w = view_z;
+        let diffuse = DIFFUSE_TEXTURE_PATHS
+            .iter()
+            .find_map(|path| File::open(path).ok())
+            .map(|file| Rc::new(read_tga(BufReader::new(file)).unwrap()));
+        if diffuse.is_none() {
+            eprintln!("Diffuse texture unavailable, rendering untextured");
+        }
+        let material = Material {
+            diffuse_texture: diffuse,
+            sampler: Sampler::new(Filter::Bilinear, WrapMode::Repeat),
+            ..Material::default()
+        };
+
+        let headlight = PositionalLight::new(
+            WorldPoint::ZERO,
+            LinSrgb::new(1.0, 1.0, 1.0),
+            0.6,
+            Attenuation::none(),
+        );
+        let key_light = DirectionalLight::new(
+            WorldVector::new(-1.0, -0.5, -1.0),
+            LinSrgb::new(1.0, 0.9, 0.75),
+            0.8,
+        );
+
+        let renderer = Renderer::new();
+
+        // The head never moves relative to the key light, so its shadow map is rendered just once
+        let mut shadow_map = ShadowMap::new(Vector2::new(512, 512));
+        shadow_map.filter = ShadowFilter::Pcf { radius: 1 };
+        renderer
+            .begin_shadow_pass(&mut shadow_map, &key_light, &[&head])
+            .draw_object(&head);
+
+        let aspect_ratio = dimensions.x as f32 / dimensions.y as f32;
+        let mut camera = PerspectiveCamera::new(
+            WorldPoint::new(0.0, 0.0, 3.5),   // Position
+            WorldVector::new(0.0, 0.0, -1.0), // Direction (looking towards negative z)
+            // Up vector
+            0.1,  // Near plane
+            10.0, // Far plane
+            37.5, // Field of view in degrees
+            aspect_ratio,
+        );
+        camera.look_at(&head);
+
+        let mut orbit = OrbitController::from_pose(camera.pose(), bounds_center(&head));
+        orbit.auto_rotate_speed = Angle::from_degrees(ORBIT_SPEED_IN_DEGREES_PER_SECOND);
+
+        Self {
+            clock: FrameClock::new(frame_mode),
+            last_stats: Instant::now(),
+            input: WinitInput::new(),
+            camera_control: CameraControl::Orbit(orbit),
+            head,
+            material,
+            shading_model: ShadingModel::Phong(SpecularModel::BlinnPhong),
+            headlight,
+            key_light,
+            shadow_map,
+            renderer,
+            depth_buffer: DepthBuffer::new(dimensions),
+            camera,
+        }
+    }
 
-    // Perform perspective divide
-    let ndc = Vector2::<f32>::new(clip_point.x / w, clip_point.y / w);
+    // Follows the target, e.g. when the window was resized
+    fn resize(&mut self, dimensions: Vector2<u32>) {
+        self.depth_buffer.resize(dimensions);
+        self.camera
+            .set_aspect_ratio(dimensions.x as f32 / dimensions.y.max(1) as f32);
+    }
 
-    ScreenPoint::new(
-        (ndc.x + 1.0) * width as f32 / 2.0,
-        (ndc.y + 1.0) * height as f32 / 2.0,
-    )
+    // Takes over from the current view either way, the orbit stays centered on the head
+    fn toggle_camera_control(&mut self) {
+        self.camera_control = match self.camera_control {
+            CameraControl::Orbit(_) => {
+                CameraControl::Fly(FlyController::from_pose(self.camera.pose()))
+            }
+            CameraControl::Fly(_) => {
+                let mut orbit =
+                    OrbitController::from_pose(self.camera.pose(), bounds_center(&self.head));
+                orbit.auto_rotate_speed = Angle::from_degrees(ORBIT_SPEED_IN_DEGREES_PER_SECOND);
+                CameraControl::Orbit(orbit)
+            }
+        };
+    }
+
+    fn toggle_shading_model(&mut self) {
+        self.shading_model = match self.shading_model {
+            ShadingModel::Phong(specular_model) => ShadingModel::Gouraud(specular_model),
+            ShadingModel::Gouraud(specular_model) => ShadingModel::Phong(specular_model),
+        };
+    }
+
+    fn update(&mut self, tick: Tick) {
+        let controller: &mut dyn CameraController = match &mut self.camera_control {
+            CameraControl::Orbit(orbit) => orbit,
+            CameraControl::Fly(fly) => fly,
+        };
+        // Dragging and scrolling count once per frame, even for frames without a fixed step, only
+        // held actions add up with the steps
+        let input = *self.input.state();
+        controller.update(&input, 0.0, self.camera.pose_mut());
+        for _ in 0..tick.steps {
+            controller.update(&input.held(), tick.delta_seconds(), self.camera.pose_mut());
+        }
+        self.input.end_frame();
+
+        self.headlight.position = self.camera.position();
+    }
+
+    fn draw<F: PixelFormat>(&mut self, frame_buffer: &mut TargetFrameBuffer<'_, '_, F>) {
+        let shadowed_key_light = ShadowedLight::new(&self.key_light, &self.shadow_map);
+        let lights: [&dyn LightSource; 2] = [&self.headlight, &shadowed_key_light];
+        let mut render_pass =
+            self.renderer
+                .begin_pass(frame_buffer, &mut self.depth_buffer, &self.camera, &lights);
+        render_pass.draw_shaded(&self.head, self.shading_model, &self.material);
+    }
+
+    fn present<T: PresentTarget>(&mut self, target: &mut T) {
+        let dimensions = target.dimensions();
+        if dimensions != self.depth_buffer.dimensions() {
+            self.resize(dimensions);
+        }
+
+        let tick = self.clock.tick();
+        if self.last_stats.elapsed() >= STATS_INTERVAL {
+            eprintln!("{}", self.clock.stats());
+            self.last_stats = Instant::now();
+        }
+
+        self.update(tick);
+        target
+            .present_frame(&mut |frame_buffer| self.draw(frame_buffer))
+            .unwrap();
+    }
 }
 
 fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mut frame_mode = FrameMode::Capped { fps: FPS_TARGET };
+    if args.first().map(String::as_str) == Some("--fps") {
+        match args.get(1).and_then(|arg| arg.parse().ok()) {
+            Some(0) => frame_mode = FrameMode::Uncapped,
+            Some(fps) => frame_mode = FrameMode::Capped { fps },
+            None => return eprintln!("{USAGE}"),
+        }
+        args.drain(..2);
+    }
+
+    match args.first().map(String::as_str) {
+        None => run_in_window(None, frame_mode),
+        Some("--fixed") => match args.get(1).and_then(|arg| parse_resolution(arg)) {
+            Some(resolution) => run_in_window(Some(resolution), frame_mode),
+            None => eprintln!("{USAGE}"),
+        },
+        Some("--fbdev") => {
+            run_on_framebuffer(args.get(1).map_or("/dev/fb0", String::as_str), frame_mode)
+        }
+        Some(_) => eprintln!("{USAGE}"),
+    }
+}
+
+fn bounds_center(object: &dyn Bounded) -> WorldPoint {
+    let bounds = object.calculate_bounding_box();
+    bounds.min + (bounds.max - bounds.min) / 2.0
+}
+
+fn parse_resolution(arg: &str) -> Option<Vector2<u32>> {
+    let (width, height) = arg.split_once('x')?;
+    let resolution = Vector2::new(width.parse().ok()?, height.parse().ok()?);
+
+    (resolution.x > 0 && resolution.y > 0).then_some(resolution)
+}
+
+// Straight from a console, without X11 or Wayland
+#[cfg(target_os = "linux")]
+fn run_on_framebuffer(device_path: &str, frame_mode: FrameMode) {
+    use sw_render::buffers::pixel_format::{Rgb565, SurfaceFormat};
+
+    let device = sw_render::present::fbdev::FramebufferDevice::open(device_path)
+        .unwrap_or_else(|error| panic!("{device_path}: {error}"));
+    let mut demo = Demo::new(device.dimensions(), frame_mode);
+
+    // Rendering in the panel's own format leaves nothing to convert
+    if device.surface_format() == SurfaceFormat::Rgb565 {
+        let mut device = device.with_format::<Rgb565>();
+        loop {
+            demo.clock.wait();
+            demo.present(&mut device);
+        }
+    } else {
+        let mut device = device;
+        loop {
+            demo.clock.wait();
+            demo.present(&mut device);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn run_on_framebuffer(_device_path: &str, _frame_mode: FrameMode) {
+    eprintln!("the framebuffer backend is only available on Linux");
+}
+
+// Frames follow the window's size, unless a fixed resolution is given to letterbox instead
+fn run_in_window(fixed_resolution: Option<Vector2<u32>>, frame_mode: FrameMode) {
     let event_loop = EventLoop::new().unwrap();
-    let start = Instant::now();
-
-    let input = BufReader::new(File::open("african_head.obj").unwrap());
-    let face_mesh = Mesh::from_obj(input).unwrap();
-
-    let wireframe_color = Srgb::<u8>::new(255, 255, 255);
-
-    let aspect_ratio = WIDTH as f32 / HEIGHT as f32;
-    let mut camera = PerspectiveCamera::new(
-        WorldPoint::new(0.0, 0.0, 2.25),  // Position
-        WorldVector::new(0.0, 0.0, -1.0), // Direction (looking towards negative z)
-        // Up vector
-        0.1,  // Near plane
-        10.0, // Far plane
-        37.5, // Field of view in degrees
-        aspect_ratio,
-    );
-
-    let mut rotate_around_y_axis: Transform3<WorldSpace, WorldSpace> =
-        Transform3::from_scale_rotation_translation(
-            Vector3::ONE,
-            Vector3::Y,
-            Angle::from_degrees(1.0),
-            Vector3::ZERO,
-        );
+    let mut demo = Demo::new(fixed_resolution.unwrap_or(DISPLAY_DIMENSIONS), frame_mode);
 
     let window_attributes = Window::default_attributes()
         .with_title("Software Renderer")
-        .with_inner_size(PhysicalSize::new(WIDTH as u32, HEIGHT as u32))
-        .with_resizable(false);
+        .with_inner_size(PhysicalSize::new(WIDTH as u32, HEIGHT as u32));
 
     let mut app = sw_render::utils::winit_app::WinitAppBuilder::with_init(|elwt| {
-        let window = {
-            let window = elwt.create_window(window_attributes.clone());
-            Rc::new(window.unwrap())
-        };
-        let context = softbuffer::Context::new(window.clone()).unwrap();
-        let surface = softbuffer::Surface::new(&context, window.clone()).unwrap();
-
-        let old_size = (0, 0);
-
-        (window, surface, old_size)
+        let window = Rc::new(elwt.create_window(window_attributes.clone()).unwrap());
+        match fixed_resolution {
+            Some(resolution) => WindowTarget::with_fixed_resolution(window, resolution).unwrap(),
+            None => WindowTarget::new(window).unwrap(),
+        }
     })
-    .with_event_handler(|state, event, elwt| {
-        let (window, surface, old_size) = state;
-        elwt.set_control_flow(ControlFlow::Wait);
+    .with_event_handler(|target, event, elwt| {
+        let window = target.window().clone();
 
         match event {
             Event::WindowEvent {
                 window_id,
                 event: WindowEvent::RedrawRequested,
             } if window_id == window.id() => {
-                let size = window.inner_size();
-                if let (Some(width), Some(height)) =
-                    (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
-                {
-                    surface
-                        .resize(
-                            NonZeroU32::new(WIDTH as u32).unwrap(),
-                            NonZeroU32::new(HEIGHT as u32).unwrap(),
-                        )
-                        .unwrap();
-
-                    if (width.get(), height.get()) != *old_size {
-                        *old_size = (width.get(), height.get());
-                    };
-
-                    let mut window_buffer = surface.buffer_mut().unwrap();
-
-                    let mut smart_buffer = FrameBuffer::new(&mut window_buffer, DISPLAY_DIMENSIONS);
-                    smart_buffer.clear();
-
-                    let elapsed_frames =
-                        (start.elapsed().as_millis() % (1_000 / FPS_TARGET) as u128) as f64
-                            / FPS_TARGET as f64;
-
-                    rotate_around_y_axis = Transform3::from_scale_rotation_translation(
-                        Vector3::ONE,
-                        Vector3::Y,
-                        Angle::from_degrees(1.0) * (elapsed_frames as f32),
-                        Vector3::ZERO,
-                    );
-                    camera.position = rotate_around_y_axis.map_point(camera.position);
-                    camera.look_at_point(&WorldPoint::ZERO);
-                    face_mesh.tris_faces().for_each(|face| {
-                        face.iter()
-                            .map(|point| transform_vertex(&point.as_(), &camera, WIDTH, HEIGHT))
-                            .circular_tuple_windows::<(_, _)>()
-                            .for_each(|(p1, p2)| smart_buffer.draw_line(&p1, &p2, wireframe_color));
-                    });
-
-                    window_buffer.present().unwrap();
-                }
+                demo.present(target);
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                logical_key: Key::Named(NamedKey::Tab),
+                                state: ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    },
+                window_id,
+            } if window_id == window.id() => {
+                demo.toggle_camera_control();
             }
-            Event::AboutToWait => {
-                window.request_redraw();
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        event:
+                            KeyEvent {
+                                logical_key: Key::Character(key),
+                                state: ElementState::Pressed,
+                                ..
+                            },
+                        ..
+                    },
+                window_id,
+            } if window_id == window.id() && key == "l" => {
+                demo.toggle_shading_model();
             }
+            // Sleeps in the event loop until the next frame is due, so input stays responsive
+            Event::AboutToWait => match demo.clock.deadline() {
+                Some(deadline) if deadline > Instant::now() => {
+                    elwt.set_control_flow(ControlFlow::WaitUntil(deadline));
+                }
+                _ => {
+                    elwt.set_control_flow(ControlFlow::Poll);
+                    window.request_redraw();
+                }
+            },
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 window_id,
             } if window_id == window.id() => {
                 elwt.exit();
             }
+            Event::WindowEvent { window_id, event } if window_id == window.id() => {
+                demo.input.handle_window_event(&event);
+            }
             _ => {}
         }
     });
@@ -1,9 +1,120 @@
-use glamour::Vector2;
+use crate::common::primitives::PolygonPoints2;
+use glamour::{Vector2, Vector3};
 
-pub struct DepthBuffer {}
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DepthCompare {
+    #[default]
+    Less,
+    LessEqual,
+    Greater,
+    Always,
+    Never,
+}
+
+impl DepthCompare {
+    pub fn passes(&self, incoming_depth: f32, stored_depth: f32) -> bool {
+        match self {
+            DepthCompare::Less => incoming_depth < stored_depth,
+            DepthCompare::LessEqual => incoming_depth <= stored_depth,
+            DepthCompare::Greater => incoming_depth > stored_depth,
+            DepthCompare::Always => true,
+            DepthCompare::Never => false,
+        }
+    }
+}
+
+pub struct DepthBuffer {
+    data: Vec<f32>,
+    width: u32,
+    height: u32,
+    compare: DepthCompare,
+    write_enabled: bool,
+}
 
 impl DepthBuffer {
-    fn new(dimensions: Vector2) -> Self {
-        todo!()
+    // Depth is stored in window coordinates, where 0.0 is the near and 1.0 the far plane
+    pub const FAR_DEPTH: f32 = 1.0;
+
+    pub fn new(dimensions: Vector2<u32>) -> Self {
+        Self {
+            data: vec![Self::FAR_DEPTH; (dimensions.x * dimensions.y) as usize],
+            width: dimensions.x,
+            height: dimensions.y,
+            compare: DepthCompare::default(),
+            write_enabled: true,
+        }
+    }
+
+    pub fn dimensions(&self) -> Vector2<u32> {
+        Vector2::new(self.width, self.height)
+    }
+
+    // Keeps the compare function and write mask, the contents are cleared
+    pub fn resize(&mut self, dimensions: Vector2<u32>) {
+        self.data = vec![Self::FAR_DEPTH; (dimensions.x * dimensions.y) as usize];
+        self.width = dimensions.x;
+        self.height = dimensions.y;
+    }
+
+    pub fn compare(&self) -> DepthCompare {
+        self.compare
+    }
+
+    pub fn set_compare(&mut self, compare: DepthCompare) {
+        self.compare = compare;
+    }
+
+    pub fn write_enabled(&self) -> bool {
+        self.write_enabled
+    }
+
+    pub fn set_write_enabled(&mut self, write_enabled: bool) {
+        self.write_enabled = write_enabled;
+    }
+
+    pub fn get_depth(&self, x: u32, y: u32) -> Option<f32> {
+        if x < self.width && y < self.height {
+            Some(self.data[(y * self.width + x) as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn test(&self, x: u32, y: u32, depth: f32) -> bool {
+        self.get_depth(x, y)
+            .is_some_and(|stored_depth| self.compare.passes(depth, stored_depth))
+    }
+
+    // Does nothing if writes are disabled
+    pub fn write(&mut self, x: u32, y: u32, depth: f32) {
+        if self.write_enabled && x < self.width && y < self.height {
+            self.data[(y * self.width + x) as usize] = depth;
+        }
+    }
+
+    // Runs the depth test and, if it passes and writes are enabled, stores the incoming depth
+    pub fn test_and_write(&mut self, x: u32, y: u32, depth: f32) -> bool {
+        if !self.test(x, y, depth) {
+            return false;
+        }
+
+        self.write(x, y, depth);
+
+        true
+    }
+
+    // Depth-only rasterization, e.g. for shadow maps
+    pub fn fill_triangle(&mut self, triangle: &PolygonPoints2, depths: [f32; 3]) {
+        triangle.rasterize(self.width, self.height, |x, y, weights| {
+            self.test_and_write(x, y, weights.dot(Vector3::from_array(depths)));
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.clear_to(Self::FAR_DEPTH);
+    }
+
+    pub fn clear_to(&mut self, depth: f32) {
+        self.data.fill(depth);
     }
 }
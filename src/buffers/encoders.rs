@@ -0,0 +1,229 @@
+use crate::buffers::image::Image;
+use palette::Srgb;
+use std::io::{Error, ErrorKind, Result, Write};
+
+// Binary Netpbm, the simplest format there is
+pub fn write_ppm<W: Write>(image: &Image, writer: &mut W) -> Result<()> {
+    let dimensions = image.dimensions();
+    write!(writer, "P6\n{} {}\n255\n", dimensions.x, dimensions.y)?;
+    for pixel in image.pixels() {
+        writer.write_all(&[pixel.red, pixel.green, pixel.blue])?;
+    }
+
+    Ok(())
+}
+
+// Uncompressed 24-bit BMP
+pub fn write_bmp<W: Write>(image: &Image, writer: &mut W) -> Result<()> {
+    const FILE_HEADER_LENGTH: u32 = 14;
+    const INFO_HEADER_LENGTH: u32 = 40;
+
+    let dimensions = image.dimensions();
+    // Rows are padded to a multiple of 4 bytes
+    let row_length = (dimensions.x * 3).div_ceil(4) * 4;
+    let data_length = row_length * dimensions.y;
+    let data_offset = FILE_HEADER_LENGTH + INFO_HEADER_LENGTH;
+
+    writer.write_all(b"BM")?;
+    writer.write_all(&(data_offset + data_length).to_le_bytes())?;
+    writer.write_all(&[0; 4])?;
+    writer.write_all(&data_offset.to_le_bytes())?;
+
+    writer.write_all(&INFO_HEADER_LENGTH.to_le_bytes())?;
+    writer.write_all(&(dimensions.x as i32).to_le_bytes())?;
+    writer.write_all(&(dimensions.y as i32).to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?; // Color planes
+    writer.write_all(&24u16.to_le_bytes())?; // Bits per pixel
+    writer.write_all(&0u32.to_le_bytes())?; // No compression
+    writer.write_all(&data_length.to_le_bytes())?;
+    writer.write_all(&2835i32.to_le_bytes())?; // 72 DPI, horizontally
+    writer.write_all(&2835i32.to_le_bytes())?; // and vertically
+    writer.write_all(&[0; 8])?; // No palette
+
+    // Stored bottom row first
+    let padding = vec![0u8; (row_length - dimensions.x * 3) as usize];
+    for y in (0..dimensions.y).rev() {
+        for x in 0..dimensions.x {
+            let pixel = image.get_pixel(x, y).unwrap_or_default();
+            writer.write_all(&[pixel.blue, pixel.green, pixel.red])?;
+        }
+        writer.write_all(&padding)?;
+    }
+
+    Ok(())
+}
+
+// RLE-compressed 24-bit TGA, which `textures::tga::read_tga` can read back
+pub fn write_tga<W: Write>(image: &Image, writer: &mut W) -> Result<()> {
+    const RLE_TRUE_COLOR: u8 = 10;
+    const TOP_TO_BOTTOM: u8 = 1 << 5;
+    const MAX_PACKET_LENGTH: usize = 128;
+
+    let dimensions = image.dimensions();
+    // The header only has 16 bits for each dimension
+    let (Ok(width), Ok(height)) = (u16::try_from(dimensions.x), u16::try_from(dimensions.y)) else {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{}x{} is too large for TGA, which allows at most {}x{}",
+                dimensions.x,
+                dimensions.y,
+                u16::MAX,
+                u16::MAX
+            ),
+        ));
+    };
+
+    let mut header = [0u8; 18];
+    header[2] = RLE_TRUE_COLOR;
+    header[12..14].copy_from_slice(&width.to_le_bytes());
+    header[14..16].copy_from_slice(&height.to_le_bytes());
+    header[16] = 24;
+    header[17] = TOP_TO_BOTTOM;
+    writer.write_all(&header)?;
+
+    let to_bgr = |pixel: &Srgb<u8>| [pixel.blue, pixel.green, pixel.red];
+
+    // Packets never span rows, as the format recommends
+    let pixels: Vec<Srgb<u8>> = image.pixels().collect();
+    for row in pixels.chunks(dimensions.x.max(1) as usize) {
+        let mut start = 0;
+        while start < row.len() {
+            let run_length = row[start..]
+                .iter()
+                .take(MAX_PACKET_LENGTH)
+                .take_while(|&&pixel| pixel == row[start])
+                .count();
+
+            if run_length > 1 {
+                writer.write_all(&[0x80 | (run_length - 1) as u8])?;
+                writer.write_all(&to_bgr(&row[start]))?;
+                start += run_length;
+            } else {
+                // Raw packets end where the next run of at least two pixels starts
+                let mut end = start + 1;
+                while end < row.len()
+                    && end - start < MAX_PACKET_LENGTH
+                    && !(end + 1 < row.len() && row[end] == row[end + 1])
+                {
+                    end += 1;
+                }
+
+                writer.write_all(&[(end - start - 1) as u8])?;
+                for pixel in &row[start..end] {
+                    writer.write_all(&to_bgr(pixel))?;
+                }
+                start = end;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// 8-bit RGB PNG. The image data is stored without compression, which keeps the encoder tiny
+// while staying readable by any decoder.
+pub fn write_png<W: Write>(image: &Image, writer: &mut W) -> Result<()> {
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+    let dimensions = image.dimensions();
+    writer.write_all(&SIGNATURE)?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&dimensions.x.to_be_bytes());
+    header.extend_from_slice(&dimensions.y.to_be_bytes());
+    // Bit depth, RGB color type, deflate, adaptive filtering, no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    write_png_chunk(writer, b"IHDR", &header)?;
+
+    // Every scanline starts with its filter type, 0 leaves it unfiltered
+    let pixels: Vec<Srgb<u8>> = image.pixels().collect();
+    let mut scanlines = Vec::with_capacity((dimensions.x * 3 + 1) as usize * dimensions.y as usize);
+    for row in pixels.chunks(dimensions.x.max(1) as usize) {
+        scanlines.push(0);
+        for pixel in row {
+            scanlines.extend_from_slice(&[pixel.red, pixel.green, pixel.blue]);
+        }
+    }
+    write_png_chunk(writer, b"IDAT", &zlib_stored(&scanlines))?;
+    write_png_chunk(writer, b"IEND", &[])?;
+
+    Ok(())
+}
+
+fn write_png_chunk<W: Write>(writer: &mut W, chunk_type: &[u8; 4], data: &[u8]) -> Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(chunk_type)?;
+    writer.write_all(data)?;
+
+    let crc = !crc32_update(crc32_update(u32::MAX, chunk_type), data);
+    writer.write_all(&crc.to_be_bytes())
+}
+
+// A zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK_LENGTH: usize = u16::MAX as usize;
+
+    let mut stream = Vec::with_capacity(data.len() + data.len() / MAX_BLOCK_LENGTH * 5 + 11);
+    // Deflate with a 32K window, no preset dictionary, header checksum making it divisible by 31
+    stream.extend_from_slice(&[0x78, 0x01]);
+
+    let mut blocks = data.chunks(MAX_BLOCK_LENGTH).peekable();
+    if blocks.peek().is_none() {
+        stream.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        stream.push(is_final as u8);
+        stream.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        stream.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 bytes is the most that can be summed before `b` could overflow
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+
+    (b << 16) | a
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+};
+
+// Expects the running CRC before the final inversion
+fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    data.iter().fold(crc, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
@@ -1,15 +1,28 @@
-use crate::common::space::{ScreenPoint, ScreenScalar};
-use glamour::Vector2;
-use palette::{rgb, Srgb};
+use crate::buffers::depth::DepthBuffer;
+use crate::buffers::pixel_format::{PixelFormat, Xrgb8888};
+use crate::common::primitives::PolygonPoints2;
+use crate::common::space::{ScreenPoint, ScreenScalar, ScreenSpace};
+use glamour::{Vector2, Vector3};
+use palette::Srgb;
+use std::marker::PhantomData;
 use std::ops::DerefMut;
 
-pub struct FrameBuffer<'a, D: DerefMut<Target = [u32]>> {
+pub struct FrameBuffer<'a, D, F = Xrgb8888>
+where
+    D: DerefMut<Target = [F::Storage]>,
+    F: PixelFormat,
+{
     data: &'a mut D,
     width: u32,
     height: u32,
+    format: PhantomData<F>,
 }
 
-impl<'a, D: DerefMut<Target = [u32]>> FrameBuffer<'a, D> {
+impl<'a, D, F> FrameBuffer<'a, D, F>
+where
+    D: DerefMut<Target = [F::Storage]>,
+    F: PixelFormat,
+{
     const INSIDE: u8 = 0b0000;
     const LEFT: u8 = 0b0001;
     const RIGHT: u8 = 0b0010;
@@ -21,9 +34,14 @@ impl<'a, D: DerefMut<Target = [u32]>> FrameBuffer<'a, D> {
             data: buffer,
             width: dimensions.x,
             height: dimensions.y,
+            format: PhantomData,
         }
     }
 
+    pub fn dimensions(&self) -> Vector2<u32> {
+        Vector2::new(self.width, self.height)
+    }
+
     // Cohen-Sutherland algorithm
     fn compute_outcode(&self, x: ScreenScalar, y: ScreenScalar) -> u8 {
         let mut code = Self::INSIDE;
@@ -93,11 +111,17 @@ impl<'a, D: DerefMut<Target = [u32]>> FrameBuffer<'a, D> {
     }
 
     pub fn set_pixel(&mut self, x: u32, y: u32, color: Srgb<u8>) {
-        let rgb_color: u32 = color.into_u32::<rgb::channels::Rgba>() >> 8;
-
         let index = y * self.width + x;
         if x < self.width && y < self.height {
-            self.data[index as usize] = rgb_color;
+            self.data[index as usize] = F::pack(color);
+        }
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Srgb<u8>> {
+        if x < self.width && y < self.height {
+            Some(F::unpack(self.data[(y * self.width + x) as usize]))
+        } else {
+            None
         }
     }
 
@@ -138,8 +162,8 @@ impl<'a, D: DerefMut<Target = [u32]>> FrameBuffer<'a, D> {
         if (outcode1 | outcode2) == Self::INSIDE {
             self.draw_line_inside(p1, p2, color);
         } else {
-            let mut cloned_p1 = p1.clone();
-            let mut cloned_p2 = p2.clone();
+            let mut cloned_p1 = *p1;
+            let mut cloned_p2 = *p2;
 
             self.clip_line(&mut cloned_p1, &mut cloned_p2, outcode1, outcode2);
 
@@ -147,7 +171,60 @@ impl<'a, D: DerefMut<Target = [u32]>> FrameBuffer<'a, D> {
         }
     }
 
+    pub fn draw_triangle(&mut self, points: &[ScreenPoint; 3], color: Srgb<u8>) {
+        self.draw_line(&points[0], &points[1], color);
+        self.draw_line(&points[1], &points[2], color);
+        self.draw_line(&points[2], &points[0], color);
+    }
+
+    pub fn fill_triangle(&mut self, triangle: &PolygonPoints2, color: Srgb<u8>) {
+        let (width, height) = (self.width, self.height);
+        triangle.rasterize(width, height, |x, y, _| self.set_pixel(x, y, color));
+    }
+
+    // Depth is interpolated linearly in screen space, which is correct for post-divide depth
+    pub fn fill_triangle_with_depth(
+        &mut self,
+        depth_buffer: &mut DepthBuffer,
+        triangle: &PolygonPoints2,
+        depths: [f32; 3],
+        color: Srgb<u8>,
+    ) {
+        let (width, height) = (self.width, self.height);
+        triangle.rasterize(width, height, |x, y, weights| {
+            let depth = weights.dot(Vector3::from_array(depths));
+            if depth_buffer.test_and_write(x, y, depth) {
+                self.set_pixel(x, y, color);
+            }
+        });
+    }
+
+    // `fragment` gets the screen-space barycentric weights of every pixel passing the depth test
+    // and returns its color, or `None` to discard it
+    pub fn shade_triangle<S>(
+        &mut self,
+        depth_buffer: &mut DepthBuffer,
+        triangle: &PolygonPoints2,
+        depths: [f32; 3],
+        mut fragment: S,
+    ) where
+        S: FnMut(Vector3<ScreenSpace>) -> Option<Srgb<u8>>,
+    {
+        let (width, height) = (self.width, self.height);
+        triangle.rasterize(width, height, |x, y, weights| {
+            let depth = weights.dot(Vector3::from_array(depths));
+            if !depth_buffer.test(x, y, depth) {
+                return;
+            }
+
+            if let Some(color) = fragment(weights) {
+                depth_buffer.write(x, y, depth);
+                self.set_pixel(x, y, color);
+            }
+        });
+    }
+
     pub fn clear(&mut self) {
-        self.data.fill(0);
+        self.data.fill(F::pack(Srgb::new(0, 0, 0)));
     }
 }
@@ -0,0 +1,110 @@
+use crate::buffers::encoders::{write_bmp, write_png, write_ppm, write_tga};
+use crate::buffers::frame::FrameBuffer;
+use crate::buffers::pixel_format::{PixelFormat, Xrgb8888};
+use derive_more::{Display, From};
+use glamour::Vector2;
+use palette::Srgb;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Bmp,
+    Tga,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => Some(ImageFormat::Ppm),
+            "bmp" => Some(ImageFormat::Bmp),
+            "tga" => Some(ImageFormat::Tga),
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Display, From)]
+pub enum ImageError {
+    #[display("failed to write image: {_0}")]
+    Io(std::io::Error),
+    #[display("unsupported image file extension, expected ppm, bmp, tga or png")]
+    #[from(ignore)]
+    UnsupportedFormat,
+}
+
+// An owned XRGB8888 render target that needs no window, e.g. for screenshots or rendering on a
+// device without a desktop session
+pub struct Image {
+    data: Vec<u32>,
+    width: u32,
+    height: u32,
+}
+
+impl Image {
+    pub fn new(dimensions: Vector2<u32>) -> Self {
+        Self {
+            data: vec![0; (dimensions.x * dimensions.y) as usize],
+            width: dimensions.x,
+            height: dimensions.y,
+        }
+    }
+
+    pub fn dimensions(&self) -> Vector2<u32> {
+        Vector2::new(self.width, self.height)
+    }
+
+    // Pixels as the frame buffer stores them, row by row starting at the top
+    pub fn data(&self) -> &[u32] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [u32] {
+        &mut self.data
+    }
+
+    pub fn frame_buffer(&mut self) -> FrameBuffer<'_, Vec<u32>> {
+        let dimensions = self.dimensions();
+        FrameBuffer::new(&mut self.data, dimensions)
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Srgb<u8>> {
+        if x < self.width && y < self.height {
+            let pixel = self.data[(y * self.width + x) as usize];
+            Some(Xrgb8888::unpack(pixel))
+        } else {
+            None
+        }
+    }
+
+    // Pixels row by row starting at the top
+    pub fn pixels(&self) -> impl Iterator<Item = Srgb<u8>> + '_ {
+        self.data.iter().map(|&pixel| Xrgb8888::unpack(pixel))
+    }
+
+    // Picks the format from the file extension
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ImageError> {
+        let format = ImageFormat::from_path(&path).ok_or(ImageError::UnsupportedFormat)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, format)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W, format: ImageFormat) -> Result<(), ImageError> {
+        match format {
+            ImageFormat::Ppm => write_ppm(self, writer)?,
+            ImageFormat::Bmp => write_bmp(self, writer)?,
+            ImageFormat::Tga => write_tga(self, writer)?,
+            ImageFormat::Png => write_png(self, writer)?,
+        }
+
+        Ok(())
+    }
+}
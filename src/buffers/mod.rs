@@ -1,3 +1,6 @@
 pub mod depth;
+pub mod encoders;
 pub mod frame;
+pub mod image;
+pub mod pixel_format;
 mod traits;
@@ -0,0 +1,108 @@
+use palette::{rgb, Srgb};
+
+// Memory layout of a surface's pixels, as reported by presentation targets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurfaceFormat {
+    Xrgb8888,
+    Rgb888,
+    Rgb565,
+    Rgba8888,
+    L8,
+    Other { bits_per_pixel: u32 },
+}
+
+// How colors are stored in a frame buffer. Rendering into the display's native format saves a
+// conversion pass per frame.
+pub trait PixelFormat {
+    // One pixel, widened losslessly into a u32 holding the same bits
+    type Storage: Copy + Into<u32> + 'static;
+
+    const SURFACE_FORMAT: SurfaceFormat;
+
+    fn pack(color: Srgb<u8>) -> Self::Storage;
+
+    fn unpack(pixel: Self::Storage) -> Srgb<u8>;
+}
+
+// 0x00RRGGBB, what softbuffer and most desktop framebuffers use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Xrgb8888;
+
+impl PixelFormat for Xrgb8888 {
+    type Storage = u32;
+
+    const SURFACE_FORMAT: SurfaceFormat = SurfaceFormat::Xrgb8888;
+
+    fn pack(color: Srgb<u8>) -> u32 {
+        color.into_u32::<rgb::channels::Rgba>() >> 8
+    }
+
+    fn unpack(pixel: u32) -> Srgb<u8> {
+        Srgb::from_u32::<rgb::channels::Rgba>(pixel << 8)
+    }
+}
+
+// 0xRRGGBBAA, always opaque
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rgba8888;
+
+impl PixelFormat for Rgba8888 {
+    type Storage = u32;
+
+    const SURFACE_FORMAT: SurfaceFormat = SurfaceFormat::Rgba8888;
+
+    fn pack(color: Srgb<u8>) -> u32 {
+        color.into_u32::<rgb::channels::Rgba>()
+    }
+
+    fn unpack(pixel: u32) -> Srgb<u8> {
+        Srgb::from_u32::<rgb::channels::Rgba>(pixel)
+    }
+}
+
+// 5 bits of red, 6 of green and 5 of blue, the Funkey S's panel format
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rgb565;
+
+impl PixelFormat for Rgb565 {
+    type Storage = u16;
+
+    const SURFACE_FORMAT: SurfaceFormat = SurfaceFormat::Rgb565;
+
+    fn pack(color: Srgb<u8>) -> u16 {
+        ((color.red as u16 >> 3) << 11)
+            | ((color.green as u16 >> 2) << 5)
+            | (color.blue as u16 >> 3)
+    }
+
+    // The high bits are repeated into the low ones, so that white stays white
+    fn unpack(pixel: u16) -> Srgb<u8> {
+        let red = (pixel >> 11) as u8 & 0x1F;
+        let green = (pixel >> 5) as u8 & 0x3F;
+        let blue = pixel as u8 & 0x1F;
+
+        Srgb::new(
+            (red << 3) | (red >> 2),
+            (green << 2) | (green >> 4),
+            (blue << 3) | (blue >> 2),
+        )
+    }
+}
+
+// 8-bit luma, weighted by Rec. 709 on the gamma-encoded channels
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct L8;
+
+impl PixelFormat for L8 {
+    type Storage = u8;
+
+    const SURFACE_FORMAT: SurfaceFormat = SurfaceFormat::L8;
+
+    fn pack(color: Srgb<u8>) -> u8 {
+        ((54 * color.red as u32 + 183 * color.green as u32 + 19 * color.blue as u32) >> 8) as u8
+    }
+
+    fn unpack(pixel: u8) -> Srgb<u8> {
+        Srgb::new(pixel, pixel, pixel)
+    }
+}
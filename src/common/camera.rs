@@ -1,14 +1,224 @@
-use crate::common::space::{ViewToClipTransform, WorldPoint, WorldToViewTransform, WorldVector};
+use crate::common::frustum::Frustum;
+use crate::common::space::{
+    ViewToClipTransform, WorldPoint, WorldToClipTransform, WorldToViewTransform, WorldVector,
+};
 use crate::common::traits::Positionable;
+use glam::{EulerRot, Mat3, Quat, Vec3};
 use glamour::prelude::*;
+use glamour::Angle;
+
+// Anything the scene can be rendered from
+pub trait Camera {
+    fn position(&self) -> WorldPoint;
+
+    // Distance to the near clip plane along the view direction
+    fn near_plane(&self) -> f32;
+
+    // Distance to the far clip plane along the view direction
+    fn far_plane(&self) -> f32;
+
+    fn view_transform(&self) -> WorldToViewTransform;
+
+    fn projection_transform(&self) -> ViewToClipTransform;
+
+    fn view_projection_transform(&self) -> WorldToClipTransform {
+        self.view_transform().then(self.projection_transform())
+    }
+
+    // What the camera can see, for skipping objects before any of their vertices are transformed
+    fn frustum(&self) -> Frustum {
+        Frustum::from_world_to_clip(&self.view_projection_transform())
+    }
+}
+
+// Where a camera is and where it looks. The orientation is a quaternion, so any direction,
+// including straight up or down, and any roll can be represented.
+pub struct CameraPose {
+    position: WorldPoint,
+    orientation: Quat,
+    // What yaw turns around and what `look_at` keeps the camera level to
+    world_up: WorldVector,
+    view_matrix: WorldToViewTransform,
+}
+
+impl CameraPose {
+    pub fn new(position: WorldPoint, forward: WorldVector) -> Self {
+        let mut pose = Self {
+            position,
+            orientation: Quat::IDENTITY,
+            world_up: WorldVector::Y,
+            view_matrix: WorldToViewTransform::IDENTITY,
+        };
+
+        pose.look_along(forward, WorldVector::Y);
+
+        pose
+    }
+
+    pub fn position(&self) -> WorldPoint {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: WorldPoint) {
+        self.position = position;
+        self.update_view_matrix();
+    }
+
+    pub fn orientation(&self) -> Quat {
+        self.orientation
+    }
+
+    // Rotates the default pose, which looks down -Z with +Y up
+    pub fn set_orientation(&mut self, orientation: Quat) {
+        self.orientation = orientation.normalize();
+        self.update_view_matrix();
+    }
+
+    pub fn forward(&self) -> WorldVector {
+        WorldVector::from_raw(self.orientation * Vec3::NEG_Z)
+    }
+
+    pub fn up(&self) -> WorldVector {
+        WorldVector::from_raw(self.orientation * Vec3::Y)
+    }
+
+    pub fn right(&self) -> WorldVector {
+        WorldVector::from_raw(self.orientation * Vec3::X)
+    }
+
+    pub fn world_up(&self) -> WorldVector {
+        self.world_up
+    }
+
+    // E.g. +Z for Z-up scenes. Keeps the view direction but levels the camera to the new up.
+    pub fn set_world_up(&mut self, world_up: WorldVector) {
+        let Some(world_up) = world_up.to_raw().try_normalize() else {
+            return;
+        };
+        self.world_up = WorldVector::from_raw(world_up);
+        self.look_along(self.forward(), self.world_up);
+    }
+
+    // Yaw turns the view counter-clockwise around the world up, pitch raises it towards the up
+    // and roll leans the camera's up to the left, all starting from looking down -Z with +Y up
+    // in a Y-up world. Pitching past ±90° flips the yaw and roll over.
+    pub fn yaw_pitch_roll(&self) -> (Angle, Angle, Angle) {
+        let local = self.level_orientation().inverse() * self.orientation;
+        let (yaw, pitch, roll) = local.to_euler(EulerRot::YXZ);
+
+        (
+            Angle::from_radians(yaw),
+            Angle::from_radians(pitch),
+            Angle::from_radians(roll),
+        )
+    }
+
+    pub fn set_yaw_pitch_roll(&mut self, yaw: Angle, pitch: Angle, roll: Angle) {
+        self.set_orientation(
+            self.level_orientation()
+                * Quat::from_euler(EulerRot::YXZ, yaw.radians, pitch.radians, roll.radians),
+        );
+    }
+
+    pub fn yaw(&self) -> Angle {
+        self.yaw_pitch_roll().0
+    }
+
+    pub fn set_yaw(&mut self, yaw: Angle) {
+        let (_, pitch, roll) = self.yaw_pitch_roll();
+        self.set_yaw_pitch_roll(yaw, pitch, roll);
+    }
+
+    pub fn pitch(&self) -> Angle {
+        self.yaw_pitch_roll().1
+    }
+
+    pub fn set_pitch(&mut self, pitch: Angle) {
+        let (yaw, _, roll) = self.yaw_pitch_roll();
+        self.set_yaw_pitch_roll(yaw, pitch, roll);
+    }
+
+    pub fn roll(&self) -> Angle {
+        self.yaw_pitch_roll().2
+    }
+
+    pub fn set_roll(&mut self, roll: Angle) {
+        let (yaw, pitch, _) = self.yaw_pitch_roll();
+        self.set_yaw_pitch_roll(yaw, pitch, roll);
+    }
+
+    // Turns the camera by `rotation`, given in world space
+    pub fn rotate(&mut self, rotation: Quat) {
+        self.set_orientation(rotation * self.orientation);
+    }
+
+    // Points the camera along `direction`, with its up as close to `up_hint` as possible. When
+    // the two are parallel, the previous up is kept, or the previous forward if that is parallel
+    // too, as if the camera had tilted there, so that the view does not spin.
+    pub fn look_along(&mut self, direction: WorldVector, up_hint: WorldVector) {
+        let Some(forward) = direction.to_raw().try_normalize() else {
+            return;
+        };
+
+        let right = [
+            up_hint.to_raw(),
+            self.up().to_raw(),
+            self.forward().to_raw(),
+        ]
+        .into_iter()
+        .map(|up| forward.cross(up))
+        .find(|right| right.length_squared() > 1e-6)
+        .map_or_else(|| forward.any_orthonormal_vector(), Vec3::normalize);
+        let up = right.cross(forward);
+
+        self.orientation = Quat::from_mat3(&Mat3::from_cols(right, up, -forward)).normalize();
+        self.update_view_matrix();
+    }
+
+    // Does nothing when `target` is the camera's own position
+    pub fn look_at_point_with_up(&mut self, target: &WorldPoint, up_hint: WorldVector) {
+        self.look_along(target - self.position, up_hint);
+    }
+
+    pub fn look_at_point(&mut self, target: &WorldPoint) {
+        self.look_at_point_with_up(target, self.world_up);
+    }
+
+    pub fn look_at_with_up<T: Positionable>(&mut self, target: &T, up_hint: WorldVector) {
+        if let Some(target_bounded) = target.as_bounded() {
+            let bounding_box = target_bounded.calculate_bounding_box();
+            let center = bounding_box.min + (bounding_box.max - bounding_box.min) / 2.0;
+            self.look_at_point_with_up(&center, up_hint);
+        } else {
+            self.look_at_point_with_up(&target.get_position(), up_hint);
+        }
+    }
+
+    pub fn look_at<T: Positionable>(&mut self, target: &T) {
+        self.look_at_with_up(target, self.world_up);
+    }
+
+    pub fn view_matrix(&self) -> WorldToViewTransform {
+        self.view_matrix
+    }
+
+    // Takes the default pose's +Y to the world up, yaw, pitch and roll are relative to this
+    fn level_orientation(&self) -> Quat {
+        Quat::from_rotation_arc(Vec3::Y, self.world_up.to_raw())
+    }
+
+    fn update_view_matrix(&mut self) {
+        self.view_matrix = view_transform(self.position, self.right(), self.up(), self.forward());
+    }
+}
 
 pub struct PerspectiveCamera {
-    pub position: WorldPoint,
-    forward: WorldVector,
-    up: WorldVector,
-    right: WorldVector,
-    pub view_matrix: WorldToViewTransform,
-    pub perspective_matrix: ViewToClipTransform,
+    pose: CameraPose,
+    field_of_view_in_degrees: f32,
+    aspect_ratio: f32,
+    near_plane: f32,
+    far_plane: f32,
+    perspective_matrix: ViewToClipTransform,
 }
 
 // This is a right-handed, column-major, Z-negative camera (follows OpenGL conventions)
@@ -21,76 +231,321 @@ impl PerspectiveCamera {
         field_of_view_in_degrees: f32,
         aspect_ratio: f32,
     ) -> Self {
-        let scale_factor = Self::calculate_scale(field_of_view_in_degrees);
-        let aspect_corrected_scale_factor = scale_factor / aspect_ratio;
+        let mut camera = Self {
+            pose: CameraPose::new(position, forward),
+            field_of_view_in_degrees,
+            aspect_ratio,
+            near_plane,
+            far_plane,
+            perspective_matrix: ViewToClipTransform::IDENTITY,
+        };
+
+        camera.update_perspective_matrix();
+
+        camera
+    }
+
+    pub fn pose(&self) -> &CameraPose {
+        &self.pose
+    }
+
+    // Orientation beyond `look_at`: yaw, pitch, roll, the world up
+    pub fn pose_mut(&mut self) -> &mut CameraPose {
+        &mut self.pose
+    }
+
+    pub fn set_position(&mut self, position: WorldPoint) {
+        self.pose.set_position(position);
+    }
+
+    pub fn forward(&self) -> WorldVector {
+        self.pose.forward()
+    }
+
+    pub fn up(&self) -> WorldVector {
+        self.pose.up()
+    }
+
+    pub fn right(&self) -> WorldVector {
+        self.pose.right()
+    }
+
+    pub fn field_of_view_in_degrees(&self) -> f32 {
+        self.field_of_view_in_degrees
+    }
+
+    // Vertical field of view
+    pub fn set_field_of_view_in_degrees(&mut self, field_of_view_in_degrees: f32) {
+        self.field_of_view_in_degrees = field_of_view_in_degrees;
+        self.update_perspective_matrix();
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
 
-        let z_range = far_plane - near_plane;
-        let z_remapping_coefficient_1 = -(far_plane + near_plane) / z_range;
-        let z_remapping_coefficient_2 = -(2.0 * far_plane * near_plane) / z_range;
+    // Width over height, has to follow the render target's dimensions to keep circles round
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+        self.update_perspective_matrix();
+    }
+
+    pub fn set_clip_planes(&mut self, near_plane: f32, far_plane: f32) {
+        self.near_plane = near_plane;
+        self.far_plane = far_plane;
+        self.update_perspective_matrix();
+    }
 
-        let perspective_matrix = ViewToClipTransform::from_matrix_unchecked(Matrix4::from_cols(
-            vec4!(aspect_corrected_scale_factor, 0.0, 0.0, 0.0),
-            vec4!(0.0, scale_factor, 0.0, 0.0),
-            vec4!(0.0, 0.0, z_remapping_coefficient_1, -1.0),
-            vec4!(0.0, 0.0, z_remapping_coefficient_2, 0.0),
-        ));
+    fn update_perspective_matrix(&mut self) {
+        self.perspective_matrix = perspective_projection(
+            self.field_of_view_in_degrees,
+            self.aspect_ratio,
+            self.near_plane,
+            self.far_plane,
+        );
+    }
+
+    pub fn look_at_point(&mut self, target: &WorldPoint) {
+        self.pose.look_at_point(target);
+    }
 
+    pub fn look_at<T: Positionable>(&mut self, target: &T) {
+        self.pose.look_at(target);
+    }
+
+    pub fn look_at_point_with_up(&mut self, target: &WorldPoint, up_hint: WorldVector) {
+        self.pose.look_at_point_with_up(target, up_hint);
+    }
+
+    pub fn look_at_with_up<T: Positionable>(&mut self, target: &T, up_hint: WorldVector) {
+        self.pose.look_at_with_up(target, up_hint);
+    }
+}
+
+impl Camera for PerspectiveCamera {
+    fn position(&self) -> WorldPoint {
+        self.pose.position()
+    }
+
+    fn near_plane(&self) -> f32 {
+        self.near_plane
+    }
+
+    fn far_plane(&self) -> f32 {
+        self.far_plane
+    }
+
+    fn view_transform(&self) -> WorldToViewTransform {
+        self.pose.view_matrix()
+    }
+
+    fn projection_transform(&self) -> ViewToClipTransform {
+        self.perspective_matrix
+    }
+}
+
+// Parallel projection, sizes do not change with distance. Right-handed and Z-negative like
+// `PerspectiveCamera`.
+pub struct OrthographicCamera {
+    pose: CameraPose,
+    // World units covered vertically, the width follows from the aspect ratio
+    view_height: f32,
+    aspect_ratio: f32,
+    near_plane: f32,
+    far_plane: f32,
+    orthographic_matrix: ViewToClipTransform,
+}
+
+impl OrthographicCamera {
+    pub fn new(
+        position: WorldPoint,
+        forward: WorldVector,
+        near_plane: f32,
+        far_plane: f32,
+        view_height: f32,
+        aspect_ratio: f32,
+    ) -> Self {
         let mut camera = Self {
-            position,
-            forward,
-            up: WorldVector::Y,
-            right: WorldVector::ZERO,
-            perspective_matrix,
-            view_matrix: WorldToViewTransform::IDENTITY,
+            pose: CameraPose::new(position, forward),
+            view_height,
+            aspect_ratio,
+            near_plane,
+            far_plane,
+            orthographic_matrix: ViewToClipTransform::IDENTITY,
         };
 
-        camera.orthonormalize_camera_base();
-        camera.update_view_matrix();
+        camera.update_orthographic_matrix();
 
         camera
     }
 
-    fn orthonormalize_camera_base(&mut self) {
-        self.forward = self.forward.normalize();
-        self.right =
-            WorldVector::from(WorldVector::Y.to_vec3a().cross(self.forward.to_vec3a())).normalize();
-        self.up =
-            WorldVector::from(self.forward.to_vec3a().cross(self.right.to_vec3a())).normalize();
+    pub fn pose(&self) -> &CameraPose {
+        &self.pose
     }
 
-    fn update_view_matrix(&mut self) {
-        self.view_matrix = WorldToViewTransform::from_matrix_unchecked(Matrix4::from_cols(
-            vec4!(self.right.x, self.right.y, self.right.z, 0.0),
-            vec4!(self.up.x, self.up.y, self.up.z, 0.0),
-            vec4!(-self.forward.x, -self.forward.y, -self.forward.z, 0.0),
-            vec4!(
-                -self.position.to_vector().dot(self.right),
-                -self.position.to_vector().dot(self.up),
-                self.position.to_vector().dot(self.forward),
-                1.0
-            ),
-        ));
+    // Orientation beyond `look_at`: yaw, pitch, roll, the world up
+    pub fn pose_mut(&mut self) -> &mut CameraPose {
+        &mut self.pose
     }
 
-    pub fn look_at_point(&mut self, target: &WorldPoint) {
-        self.forward = (target - self.position).normalize();
+    pub fn set_position(&mut self, position: WorldPoint) {
+        self.pose.set_position(position);
+    }
 
-        self.orthonormalize_camera_base();
+    pub fn forward(&self) -> WorldVector {
+        self.pose.forward()
+    }
 
-        self.update_view_matrix();
+    pub fn up(&self) -> WorldVector {
+        self.pose.up()
+    }
+
+    pub fn right(&self) -> WorldVector {
+        self.pose.right()
+    }
+
+    pub fn view_height(&self) -> f32 {
+        self.view_height
+    }
+
+    // Zooms in or out, the orthographic equivalent of the field of view
+    pub fn set_view_height(&mut self, view_height: f32) {
+        self.view_height = view_height;
+        self.update_orthographic_matrix();
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    // Width over height, has to follow the render target's dimensions to keep circles round
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+        self.update_orthographic_matrix();
+    }
+
+    // Unlike with perspective, the near plane may be zero or even behind the camera
+    pub fn set_clip_planes(&mut self, near_plane: f32, far_plane: f32) {
+        self.near_plane = near_plane;
+        self.far_plane = far_plane;
+        self.update_orthographic_matrix();
+    }
+
+    fn update_orthographic_matrix(&mut self) {
+        let half_height = self.view_height / 2.0;
+        let half_width = half_height * self.aspect_ratio;
+        self.orthographic_matrix = orthographic_projection(
+            -half_width,
+            half_width,
+            -half_height,
+            half_height,
+            self.near_plane,
+            self.far_plane,
+        );
+    }
+
+    pub fn look_at_point(&mut self, target: &WorldPoint) {
+        self.pose.look_at_point(target);
     }
 
     pub fn look_at<T: Positionable>(&mut self, target: &T) {
-        if let Some(target_bounded) = target.as_bounded() {
-            let bounding_box = target_bounded.calculate_bounding_box();
-            let center = bounding_box.min + (bounding_box.max - bounding_box.min) / 2.0;
-            self.look_at_point(&center);
-        } else {
-            self.look_at_point(&target.get_position());
-        }
+        self.pose.look_at(target);
     }
 
-    fn calculate_scale(field_of_view_in_degrees: f32) -> f32 {
-        (field_of_view_in_degrees.to_radians() / 2.0).tan().recip()
+    pub fn look_at_point_with_up(&mut self, target: &WorldPoint, up_hint: WorldVector) {
+        self.pose.look_at_point_with_up(target, up_hint);
     }
+
+    pub fn look_at_with_up<T: Positionable>(&mut self, target: &T, up_hint: WorldVector) {
+        self.pose.look_at_with_up(target, up_hint);
+    }
+}
+
+impl Camera for OrthographicCamera {
+    fn position(&self) -> WorldPoint {
+        self.pose.position()
+    }
+
+    fn near_plane(&self) -> f32 {
+        self.near_plane
+    }
+
+    fn far_plane(&self) -> f32 {
+        self.far_plane
+    }
+
+    fn view_transform(&self) -> WorldToViewTransform {
+        self.pose.view_matrix()
+    }
+
+    fn projection_transform(&self) -> ViewToClipTransform {
+        self.orthographic_matrix
+    }
+}
+
+// Expects an orthonormal basis, the view looks down its negative Z axis
+pub(crate) fn view_transform(
+    position: WorldPoint,
+    right: WorldVector,
+    up: WorldVector,
+    forward: WorldVector,
+) -> WorldToViewTransform {
+    WorldToViewTransform::from_matrix_unchecked(Matrix4::from_cols(
+        vec4!(right.x, up.x, -forward.x, 0.0),
+        vec4!(right.y, up.y, -forward.y, 0.0),
+        vec4!(right.z, up.z, -forward.z, 0.0),
+        vec4!(
+            -position.to_vector().dot(right),
+            -position.to_vector().dot(up),
+            position.to_vector().dot(forward),
+            1.0
+        ),
+    ))
+}
+
+pub fn perspective_projection(
+    field_of_view_in_degrees: f32,
+    aspect_ratio: f32,
+    near_plane: f32,
+    far_plane: f32,
+) -> ViewToClipTransform {
+    let scale_factor = (field_of_view_in_degrees.to_radians() / 2.0).tan().recip();
+    let aspect_corrected_scale_factor = scale_factor / aspect_ratio;
+
+    let z_range = far_plane - near_plane;
+    let z_remapping_coefficient_1 = -(far_plane + near_plane) / z_range;
+    let z_remapping_coefficient_2 = -(2.0 * far_plane * near_plane) / z_range;
+
+    ViewToClipTransform::from_matrix_unchecked(Matrix4::from_cols(
+        vec4!(aspect_corrected_scale_factor, 0.0, 0.0, 0.0),
+        vec4!(0.0, scale_factor, 0.0, 0.0),
+        vec4!(0.0, 0.0, z_remapping_coefficient_1, -1.0),
+        vec4!(0.0, 0.0, z_remapping_coefficient_2, 0.0),
+    ))
+}
+
+// Maps the view-space box from (left, bottom, -near) to (right, top, -far) onto clip space
+pub fn orthographic_projection(
+    left: f32,
+    right: f32,
+    bottom: f32,
+    top: f32,
+    near_plane: f32,
+    far_plane: f32,
+) -> ViewToClipTransform {
+    let width = right - left;
+    let height = top - bottom;
+    let depth = far_plane - near_plane;
+
+    ViewToClipTransform::from_matrix_unchecked(Matrix4::from_cols(
+        vec4!(2.0 / width, 0.0, 0.0, 0.0),
+        vec4!(0.0, 2.0 / height, 0.0, 0.0),
+        vec4!(0.0, 0.0, -2.0 / depth, 0.0),
+        vec4!(
+            -(right + left) / width,
+            -(top + bottom) / height,
+            -(far_plane + near_plane) / depth,
+            1.0
+        ),
+    ))
 }
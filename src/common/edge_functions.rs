@@ -0,0 +1,281 @@
+use crate::common::space::{ScreenPoint, ScreenScalar, ScreenSpace};
+use glamour::Vector3;
+
+// Vertices are snapped to 1/16th of a pixel. With 32-bit edge functions that leaves room for
+// triangles spanning up to 2048 pixels, anything bigger falls back to 64-bit evaluation.
+pub const SUBPIXEL_BITS: u32 = 4;
+const SUBPIXEL_ONE: i64 = 1 << SUBPIXEL_BITS;
+const SUBPIXEL_HALF: i64 = SUBPIXEL_ONE / 2;
+
+// Coordinates are clamped to ±2^29 subpixels, about 33 million pixels, so that the edge
+// functions fit in 64 bits. Clipping keeps real triangles far inside that.
+const MAX_FIXED: i64 = 1 << 29;
+
+// Pixels evaluated together along a row, four 32-bit lanes fill one NEON register
+pub const BLOCK_WIDTH: usize = 4;
+
+type FixedPoint = (i64, i64);
+
+fn to_fixed(point: ScreenPoint) -> FixedPoint {
+    let snap = |coordinate: ScreenScalar| {
+        ((coordinate * SUBPIXEL_ONE as ScreenScalar).round() as i64).clamp(-MAX_FIXED, MAX_FIXED)
+    };
+
+    (snap(point.x), snap(point.y))
+}
+
+// Fixed-point coordinates of a pixel's center
+fn pixel_center(x: u32, y: u32) -> FixedPoint {
+    (
+        x as i64 * SUBPIXEL_ONE + SUBPIXEL_HALF,
+        y as i64 * SUBPIXEL_ONE + SUBPIXEL_HALF,
+    )
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Edge {
+    from: FixedPoint,
+    delta: FixedPoint,
+    // Smallest value still counted as inside, 1 rather than 0 for edges the fill rule excludes
+    threshold: i64,
+}
+
+impl Edge {
+    // Oriented by `sign` so that the polygon's interior is positive
+    fn new(from: FixedPoint, to: FixedPoint, sign: i64) -> Self {
+        let delta = ((to.0 - from.0) * sign, (to.1 - from.1) * sign);
+
+        // Screen space has Y pointing down, so the interior lies to the right of each edge
+        let is_top_edge = delta.1 == 0 && delta.0 > 0;
+        let is_left_edge = delta.1 < 0;
+
+        Self {
+            from,
+            delta,
+            threshold: if is_top_edge || is_left_edge { 0 } else { 1 },
+        }
+    }
+
+    // Exact as long as the points stay near the triangle's snapped corners. Those are within
+    // ±2^29, so the differences stay within ±2^30 and each product within ±2^60, with room to
+    // spare for the few pixels the block path evaluates past the bounds.
+    fn evaluate(&self, point: FixedPoint) -> i64 {
+        self.delta.0 * (point.1 - self.from.1) - self.delta.1 * (point.0 - self.from.0)
+    }
+
+    // Change of the value when moving one pixel to the right
+    fn step_x(&self) -> i64 {
+        -self.delta.1 * SUBPIXEL_ONE
+    }
+
+    // Change of the value when moving one pixel down
+    fn step_y(&self) -> i64 {
+        self.delta.0 * SUBPIXEL_ONE
+    }
+}
+
+// The three edge functions of a triangle in fixed point. Edge `idx` is the one opposite to
+// point `idx`, so its value divided by the doubled area is that point's barycentric weight.
+#[derive(Clone, Copy, Debug)]
+pub struct EdgeFunctions {
+    edges: [Edge; 3],
+    points: [FixedPoint; 3],
+    double_area: i64,
+    double_area_inv: ScreenScalar,
+}
+
+impl EdgeFunctions {
+    pub fn new(points: &[ScreenPoint; 3]) -> Self {
+        let points = points.map(to_fixed);
+        let unoriented_double_area = Edge::new(points[0], points[1], 1).evaluate(points[2]);
+        let sign = if unoriented_double_area < 0 { -1 } else { 1 };
+        let double_area = unoriented_double_area * sign;
+
+        Self {
+            edges: [
+                Edge::new(points[1], points[2], sign),
+                Edge::new(points[2], points[0], sign),
+                Edge::new(points[0], points[1], sign),
+            ],
+            points,
+            double_area,
+            double_area_inv: (double_area as ScreenScalar).recip(),
+        }
+    }
+
+    // True if the triangle collapses to a line or a point once snapped to the subpixel grid
+    pub fn is_degenerate(&self) -> bool {
+        self.double_area == 0
+    }
+
+    // Barycentric weights of a fixed-point point, if it passes the top-left fill rule
+    fn weights(&self, values: [i64; 3]) -> Option<Vector3<ScreenSpace>> {
+        if values
+            .iter()
+            .zip(&self.edges)
+            .any(|(value, edge)| *value < edge.threshold)
+        {
+            return None;
+        }
+
+        Some(self.normalize(values))
+    }
+
+    // Both paths have to convert through here, so they agree bit for bit
+    fn normalize(&self, values: [i64; 3]) -> Vector3<ScreenSpace> {
+        Vector3::new(
+            values[0] as ScreenScalar * self.double_area_inv,
+            values[1] as ScreenScalar * self.double_area_inv,
+            values[2] as ScreenScalar * self.double_area_inv,
+        )
+    }
+
+    pub fn coverage(&self, probe_point: ScreenPoint) -> Option<Vector3<ScreenSpace>> {
+        if self.is_degenerate() {
+            return None;
+        }
+
+        let probe_point = to_fixed(probe_point);
+        self.weights(self.edges.map(|edge| edge.evaluate(probe_point)))
+    }
+
+    // Inclusive range of pixels whose centers may be covered, clamped to a `width` x `height`
+    // target
+    fn pixel_bounds(&self, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        let min_x = self.points.iter().map(|point| point.0).min()?;
+        let min_y = self.points.iter().map(|point| point.1).min()?;
+        let max_x = self.points.iter().map(|point| point.0).max()?;
+        let max_y = self.points.iter().map(|point| point.1).max()?;
+
+        // First and last pixel centers inside [min, max]
+        let first_pixel =
+            |min: i64| (min - SUBPIXEL_HALF + SUBPIXEL_ONE - 1).div_euclid(SUBPIXEL_ONE);
+        let last_pixel = |max: i64| (max - SUBPIXEL_HALF).div_euclid(SUBPIXEL_ONE);
+
+        let min_x = first_pixel(min_x).max(0);
+        let min_y = first_pixel(min_y).max(0);
+        let max_x = last_pixel(max_x).min(width as i64 - 1);
+        let max_y = last_pixel(max_y).min(height as i64 - 1);
+
+        if min_x <= max_x && min_y <= max_y {
+            Some((min_x as u32, min_y as u32, max_x as u32, max_y as u32))
+        } else {
+            None
+        }
+    }
+
+    // Reference implementation, evaluates every edge function from scratch at every pixel
+    pub fn rasterize_scalar<F: FnMut(u32, u32, Vector3<ScreenSpace>)>(
+        &self,
+        width: u32,
+        height: u32,
+        mut f: F,
+    ) {
+        if self.is_degenerate() {
+            return;
+        }
+        let Some((min_x, min_y, max_x, max_y)) = self.pixel_bounds(width, height) else {
+            return;
+        };
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let center = pixel_center(x, y);
+                if let Some(weights) = self.weights(self.edges.map(|edge| edge.evaluate(center))) {
+                    f(x, y, weights);
+                }
+            }
+        }
+    }
+
+    // Same output as `rasterize_scalar`, but steps the edge functions incrementally in 32 bits
+    // over `BLOCK_WIDTH` x 1 blocks, which vectorizes into one NEON register per edge
+    pub fn rasterize<F: FnMut(u32, u32, Vector3<ScreenSpace>)>(
+        &self,
+        width: u32,
+        height: u32,
+        mut f: F,
+    ) {
+        if self.is_degenerate() {
+            return;
+        }
+        let Some((min_x, min_y, max_x, max_y)) = self.pixel_bounds(width, height) else {
+            return;
+        };
+
+        // The last block of a row may stick out past `max_x`, those lanes get masked
+        let block_count = (max_x - min_x) as usize / BLOCK_WIDTH + 1;
+        let overshoot_x = min_x + (block_count * BLOCK_WIDTH) as u32 - 1;
+        if !self.fits_in_32_bits(min_x, min_y, overshoot_x, max_y) {
+            return self.rasterize_scalar(width, height, f);
+        }
+
+        let origin = pixel_center(min_x, min_y);
+        let mut row_values = self.edges.map(|edge| edge.evaluate(origin) as i32);
+        let step_x = self.edges.map(|edge| edge.step_x() as i32);
+        let step_y = self.edges.map(|edge| edge.step_y() as i32);
+        let thresholds = self.edges.map(|edge| edge.threshold as i32);
+        let lane_offsets = step_x
+            .map(|step| std::array::from_fn::<i32, BLOCK_WIDTH, _>(|lane| step * lane as i32));
+        let block_step_x = step_x.map(|step| step * BLOCK_WIDTH as i32);
+
+        for y in min_y..=max_y {
+            let mut block_values = row_values;
+            for block_idx in 0..block_count {
+                let block_x = min_x + (block_idx * BLOCK_WIDTH) as u32;
+
+                let lane_values: [[i32; BLOCK_WIDTH]; 3] = std::array::from_fn(|edge_idx| {
+                    std::array::from_fn(|lane| {
+                        block_values[edge_idx] + lane_offsets[edge_idx][lane]
+                    })
+                });
+                // Non-short-circuiting, so that all lanes get compared at once
+                let covered: [bool; BLOCK_WIDTH] = std::array::from_fn(|lane| {
+                    (lane_values[0][lane] >= thresholds[0])
+                        & (lane_values[1][lane] >= thresholds[1])
+                        & (lane_values[2][lane] >= thresholds[2])
+                });
+
+                if covered.contains(&true) {
+                    for (lane, _) in covered.iter().enumerate().filter(|(_, &covered)| covered) {
+                        let x = block_x + lane as u32;
+                        if x <= max_x {
+                            let values =
+                                [0, 1, 2].map(|edge_idx| lane_values[edge_idx][lane] as i64);
+                            f(x, y, self.normalize(values));
+                        }
+                    }
+                }
+
+                // Past the last block the values may leave the checked range, but are never read
+                for edge_idx in 0..3 {
+                    block_values[edge_idx] =
+                        block_values[edge_idx].wrapping_add(block_step_x[edge_idx]);
+                }
+            }
+
+            for edge_idx in 0..3 {
+                row_values[edge_idx] = row_values[edge_idx].wrapping_add(step_y[edge_idx]);
+            }
+        }
+    }
+
+    // Edge functions are affine, so their extremes over a rectangle lie on its corners. The
+    // incremental steps stay in range too, as they are differences of in-range values.
+    fn fits_in_32_bits(&self, min_x: u32, min_y: u32, max_x: u32, max_y: u32) -> bool {
+        let corners = [
+            (min_x, min_y),
+            (max_x, min_y),
+            (min_x, max_y),
+            (max_x, max_y),
+        ];
+
+        self.edges.iter().all(|edge| {
+            corners
+                .iter()
+                .all(|&(x, y)| i32::try_from(edge.evaluate(pixel_center(x, y))).is_ok())
+                && i32::try_from(edge.step_x() * BLOCK_WIDTH as i64).is_ok()
+                && i32::try_from(edge.step_y()).is_ok()
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use crate::common::space::{WorldBox, WorldPoint, WorldScalar, WorldToClipTransform, WorldVector};
+use crate::common::traits::Bounded;
+
+// Points with `normal.dot(point) + distance >= 0` are on its inner side
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    pub normal: WorldVector,
+    pub distance: WorldScalar,
+}
+
+impl Plane {
+    // Positive on the inner side, in world units since the normal has unit length
+    pub fn signed_distance(&self, point: WorldPoint) -> WorldScalar {
+        self.normal.dot(point.to_vector()) + self.distance
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingSphere {
+    pub center: WorldPoint,
+    pub radius: WorldScalar,
+}
+
+impl BoundingSphere {
+    // The sphere through the box's corners, loose but cheap to test
+    pub fn enclosing(bounds: &WorldBox) -> Self {
+        let half_diagonal = (bounds.max - bounds.min) / 2.0;
+        Self {
+            center: bounds.min + half_diagonal,
+            radius: half_diagonal.length(),
+        }
+    }
+}
+
+// The volume a camera sees, as six world-space planes facing inwards: left, right, bottom, top,
+// near and far
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+impl Frustum {
+    // Gribb-Hartmann extraction, each plane is the W row plus or minus the X, Y or Z row, as clip
+    // space keeps -w <= x, y, z <= w
+    pub fn from_world_to_clip(world_to_clip: &WorldToClipTransform) -> Self {
+        let matrix = &world_to_clip.matrix;
+        let (x, y, z, w) = (matrix.row(0), matrix.row(1), matrix.row(2), matrix.row(3));
+
+        Self {
+            planes: [w + x, w - x, w + y, w - y, w + z, w - z].map(|row| {
+                let normal = WorldVector::new(row.x, row.y, row.z);
+                let length = normal.length();
+                Plane {
+                    normal: normal / length,
+                    distance: row.w / length,
+                }
+            }),
+        }
+    }
+
+    pub fn planes(&self) -> &[Plane; 6] {
+        &self.planes
+    }
+
+    pub fn contains_point(&self, point: WorldPoint) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    // False only if the box is entirely outside. Boxes near the corners, outside the frustum but
+    // straddling two of its planes, count as intersecting.
+    pub fn intersects_box(&self, bounds: &WorldBox) -> bool {
+        self.planes.iter().all(|plane| {
+            // The corner furthest along the normal is the last one to leave the inner side
+            let corner = WorldPoint::new(
+                if plane.normal.x >= 0.0 {
+                    bounds.max.x
+                } else {
+                    bounds.min.x
+                },
+                if plane.normal.y >= 0.0 {
+                    bounds.max.y
+                } else {
+                    bounds.min.y
+                },
+                if plane.normal.z >= 0.0 {
+                    bounds.max.z
+                } else {
+                    bounds.min.z
+                },
+            );
+            plane.signed_distance(corner) >= 0.0
+        })
+    }
+
+    // Same as `intersects_box`, also conservative near the corners
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(sphere.center) >= -sphere.radius)
+    }
+
+    // The object is outside if either of its bounds is, whichever is tighter for its shape
+    pub fn intersects(&self, object: &dyn Bounded) -> bool {
+        self.intersects_sphere(&object.calculate_bounding_sphere())
+            && self.intersects_box(&object.calculate_bounding_box())
+    }
+}
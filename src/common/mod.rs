@@ -1,4 +1,6 @@
 pub mod camera;
+pub mod edge_functions;
+pub mod frustum;
 pub mod primitives;
 pub mod space;
 pub mod traits;
@@ -1,19 +1,11 @@
+use crate::common::edge_functions::EdgeFunctions;
 use crate::common::space::{ScreenPoint, ScreenScalar, ScreenSpace};
 use glamour::prelude::*;
 use num::traits::ConstOne;
 
-struct BarycentricPrecomputes {
-    ab_edge: Vector2<ScreenSpace>,
-    ac_edge: Vector2<ScreenSpace>,
-    dot_ab: ScreenScalar,
-    dot_ac: ScreenScalar,
-    dot_ab_ac: ScreenScalar,
-    cramer_denominator_inv: ScreenScalar,
-}
-
 pub struct PolygonPoints2 {
     points: [ScreenPoint; 3],
-    barycentric_precomputes: BarycentricPrecomputes,
+    edge_functions: EdgeFunctions,
 }
 
 impl PolygonPoints2 {
@@ -22,46 +14,41 @@ impl PolygonPoints2 {
     const POINT_C_IDX: usize = 2;
 
     pub fn new(points: [ScreenPoint; 3]) -> Self {
-        let ab_edge = points[Self::POINT_B_IDX] - points[Self::POINT_A_IDX];
-        let ac_edge = points[Self::POINT_C_IDX] - points[Self::POINT_A_IDX];
-        let dot_ab = ab_edge.dot(ab_edge);
-        let dot_ac = ac_edge.dot(ac_edge);
-        let dot_ab_ac = ab_edge.dot(ac_edge);
-        let cramer_denominator_inv = (dot_ab * dot_ac - dot_ab_ac * dot_ab_ac).recip();
-
         Self {
             points,
-            barycentric_precomputes: BarycentricPrecomputes {
-                ab_edge,
-                ac_edge,
-                dot_ab,
-                dot_ac,
-                dot_ab_ac,
-                cramer_denominator_inv,
-            },
+            edge_functions: EdgeFunctions::new(&points),
         }
     }
 
+    pub fn points(&self) -> &[ScreenPoint; 3] {
+        &self.points
+    }
+
+    // Zero-area polygons cover no pixels
+    pub fn is_degenerate(&self) -> bool {
+        self.edge_functions.is_degenerate()
+    }
+
+    // Barycentric weights in floating point, without snapping or a fill rule. Not used when
+    // rasterizing, so it is solved from scratch on every call.
     pub fn barycentric(&self, probe_point: ScreenPoint) -> Option<Vector3<ScreenSpace>> {
-        if self
-            .barycentric_precomputes
-            .cramer_denominator_inv
-            .is_infinite()
-        {
+        let ab_edge = self.points[Self::POINT_B_IDX] - self.points[Self::POINT_A_IDX];
+        let ac_edge = self.points[Self::POINT_C_IDX] - self.points[Self::POINT_A_IDX];
+        let dot_ab = ab_edge.dot(ab_edge);
+        let dot_ac = ac_edge.dot(ac_edge);
+        let dot_ab_ac = ab_edge.dot(ac_edge);
+        let cramer_denominator_inv = (dot_ab * dot_ac - dot_ab_ac * dot_ab_ac).recip();
+        if cramer_denominator_inv.is_infinite() {
             return None;
         };
 
         let ap_vec = probe_point - self.points[Self::POINT_A_IDX];
 
-        let dot_ap_ab = ap_vec.dot(self.barycentric_precomputes.ab_edge);
-        let dot_ap_ac = ap_vec.dot(self.barycentric_precomputes.ac_edge);
+        let dot_ap_ab = ap_vec.dot(ab_edge);
+        let dot_ap_ac = ap_vec.dot(ac_edge);
 
-        let v = (self.barycentric_precomputes.dot_ac * dot_ap_ab
-            - self.barycentric_precomputes.dot_ab_ac * dot_ap_ac)
-            * self.barycentric_precomputes.cramer_denominator_inv;
-        let w = (self.barycentric_precomputes.dot_ab * dot_ap_ac
-            - self.barycentric_precomputes.dot_ab_ac * dot_ap_ab)
-            * self.barycentric_precomputes.cramer_denominator_inv;
+        let v = (dot_ac * dot_ap_ab - dot_ab_ac * dot_ap_ac) * cramer_denominator_inv;
+        let w = (dot_ab * dot_ap_ac - dot_ab_ac * dot_ap_ab) * cramer_denominator_inv;
         let u = ScreenScalar::ONE - v - w;
 
         let uvw = Vector3::<ScreenSpace>::new(u, v, w);
@@ -73,6 +60,42 @@ impl PolygonPoints2 {
         }
     }
 
+    // Same as `barycentric`, but evaluated on the rasterizer's subpixel grid. Points lying exactly
+    // on an edge are only covered if that edge is a top or a left edge, so triangles sharing an
+    // edge never both claim the same pixel.
+    pub fn coverage(&self, probe_point: ScreenPoint) -> Option<Vector3<ScreenSpace>> {
+        self.edge_functions.coverage(probe_point)
+    }
+
+    // Calls `f` with the pixel coordinates and barycentric weights of every pixel whose center
+    // is covered by the polygon, limited to a `width` x `height` target
+    pub fn rasterize<F: FnMut(u32, u32, Vector3<ScreenSpace>)>(
+        &self,
+        width: u32,
+        height: u32,
+        f: F,
+    ) {
+        if self.is_degenerate() {
+            return;
+        }
+
+        self.edge_functions.rasterize(width, height, f);
+    }
+
+    // Pixel by pixel version of `rasterize` with identical output, kept as a reference
+    pub fn rasterize_scalar<F: FnMut(u32, u32, Vector3<ScreenSpace>)>(
+        &self,
+        width: u32,
+        height: u32,
+        f: F,
+    ) {
+        if self.is_degenerate() {
+            return;
+        }
+
+        self.edge_functions.rasterize_scalar(width, height, f);
+    }
+
     pub fn bounding_box(&self) -> Rect<ScreenSpace> {
         Rect::<ScreenSpace>::from_points(self.points)
     }
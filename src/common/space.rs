@@ -1,4 +1,5 @@
-use glamour::{Box3, Point2, Point3, Size2, Size3, Transform3, Unit, Vector2, Vector3};
+use glam::{Mat3, Mat4};
+use glamour::{Box3, Point2, Point3, Point4, Size3, Transform3, Unit, Vector2, Vector3, Vector4};
 
 // Model Space
 
@@ -16,6 +17,58 @@ pub type ModelBox = Box3<ModelSpace>;
 
 pub type ModelToWorldTransform = Transform3<ModelSpace, WorldSpace>;
 
+// The box is transformed corner by corner, so rotated boxes get a world box that still encloses
+// all of them
+pub fn to_world_box(transform: &ModelToWorldTransform, model_box: &ModelBox) -> WorldBox {
+    let mut world_box = WorldBox::new(
+        WorldPoint::new(f32::MAX, f32::MAX, f32::MAX),
+        WorldPoint::new(f32::MIN, f32::MIN, f32::MIN),
+    );
+    for corner_idx in 0..8 {
+        let corner = ModelPoint::new(
+            if corner_idx & 1 == 0 {
+                model_box.min.x
+            } else {
+                model_box.max.x
+            },
+            if corner_idx & 2 == 0 {
+                model_box.min.y
+            } else {
+                model_box.max.y
+            },
+            if corner_idx & 4 == 0 {
+                model_box.min.z
+            } else {
+                model_box.max.z
+            },
+        );
+        let world_corner = transform.map_point(corner);
+        world_box.min = world_box.min.min(world_corner);
+        world_box.max = world_box.max.max(world_corner);
+    }
+
+    world_box
+}
+
+// Maps normals the way `transform` maps the surfaces they belong to. That takes the inverse
+// transpose of its linear part, as non-uniform scaling would skew normals transformed like
+// positions. The results are no longer unit length.
+pub fn to_normal_transform(transform: &ModelToWorldTransform) -> ModelToWorldTransform {
+    let linear = Mat3::from_mat4(transform.matrix.into());
+    if linear.determinant() == 0.0 {
+        // Flattened to nothing, the surface has no direction left to be normal to
+        return *transform;
+    }
+
+    ModelToWorldTransform::from_matrix_unchecked(
+        Mat4::from_mat3(linear.inverse().transpose()).into(),
+    )
+}
+
+// Model -> Clip Transformation
+
+pub type ModelToClipTransform = Transform3<ModelSpace, ClipSpace>;
+
 // World Space
 
 pub struct WorldSpace;
@@ -35,6 +88,10 @@ pub type WorldBox = Box3<WorldSpace>;
 
 pub type WorldToViewTransform = Transform3<WorldSpace, ViewSpace>;
 
+// World -> Clip Transformation
+
+pub type WorldToClipTransform = Transform3<WorldSpace, ClipSpace>;
+
 // View Space
 
 pub struct ViewSpace;
@@ -61,6 +118,19 @@ pub type ClipVector = Vector3<ClipSpace>;
 pub type ClipPoint = Point3<ClipSpace>;
 pub type ClipScalar = <ClipSpace as Unit>::Scalar;
 
+// Clip space before the perspective divide, clipping happens on these
+pub type HomogeneousClipPoint = Point4<ClipSpace>;
+
+// Unlike `Transform3::map_point`, this keeps the W coordinate instead of dividing by it
+pub fn to_homogeneous_clip_point<Src: Unit<Scalar = ClipScalar>>(
+    transform: &Transform3<Src, ClipSpace>,
+    point: Point3<Src>,
+) -> HomogeneousClipPoint {
+    HomogeneousClipPoint::from_vector(
+        transform.matrix * Vector4::<ClipSpace>::new(point.x, point.y, point.z, 1.0),
+    )
+}
+
 // Clip -> Screen Transform
 
 pub type ClipToScreenTransform = Transform3<ClipSpace, ScreenSpace>;
@@ -75,3 +145,15 @@ impl Unit for ScreenSpace {
 pub type ScreenVector = Vector2<ScreenSpace>;
 pub type ScreenPoint = Point2<ScreenSpace>;
 pub type ScreenScalar = <ScreenSpace as Unit>::Scalar;
+
+// Texture Space
+
+// Normalized texture coordinates, (0, 0) and (1, 1) are opposite corners of the texture
+pub struct TextureSpace;
+impl Unit for TextureSpace {
+    type Scalar = f32;
+}
+
+pub type TextureVector = Vector2<TextureSpace>;
+pub type TexturePoint = Point2<TextureSpace>;
+pub type TextureScalar = <TextureSpace as Unit>::Scalar;
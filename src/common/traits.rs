@@ -1,3 +1,4 @@
+use crate::common::frustum::BoundingSphere;
 use crate::common::space::{WorldBox, WorldPoint, WorldSize};
 
 pub trait Positionable {
@@ -18,4 +19,9 @@ pub trait Bounded: Positionable + Dimensionable {
         let dimensions = self.get_dimensions();
         WorldBox::new(position, position + dimensions.to_vector())
     }
+
+    // Encloses the bounding box unless overridden, round objects can do much better
+    fn calculate_bounding_sphere(&self) -> BoundingSphere {
+        BoundingSphere::enclosing(&self.calculate_bounding_box())
+    }
 }
@@ -0,0 +1,98 @@
+use crate::common::camera::CameraPose;
+use crate::common::space::WorldVector;
+use crate::controls::input::{Action, InputState};
+use crate::controls::traits::CameraController;
+use glamour::Angle;
+
+// Looking further up or down would flip the yaw over
+const MAX_PITCH_IN_DEGREES: f32 = 89.0;
+
+// Speed is multiplied by this for every wheel line scrolled away from the user
+const SCROLL_SPEED_FACTOR: f32 = 1.1;
+
+// First-person free flight: turning keeps the horizon level to the world up unless rolled, moving
+// follows the view direction
+pub struct FlyController {
+    pub yaw: Angle,
+    pub pitch: Angle,
+    pub roll: Angle,
+    // World units per second
+    pub speed: f32,
+    // Per second while a turn action is held
+    pub turn_speed: Angle,
+    // Per second while a roll action is held
+    pub roll_speed: Angle,
+    // Per pixel the pointer is dragged
+    pub pointer_sensitivity: Angle,
+}
+
+impl FlyController {
+    pub fn new() -> Self {
+        Self {
+            yaw: Angle::from_radians(0.0),
+            pitch: Angle::from_radians(0.0),
+            roll: Angle::from_radians(0.0),
+            speed: 1.0,
+            turn_speed: Angle::from_degrees(90.0),
+            roll_speed: Angle::from_degrees(90.0),
+            pointer_sensitivity: Angle::from_degrees(0.2),
+        }
+    }
+
+    // Picks up from wherever `pose` currently looks, so that switching controllers does not jump
+    pub fn from_pose(pose: &CameraPose) -> Self {
+        let (yaw, pitch, roll) = pose.yaw_pitch_roll();
+        Self {
+            yaw,
+            pitch,
+            roll,
+            ..Self::new()
+        }
+    }
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CameraController for FlyController {
+    // Moving up and down follows the world up rather than the view, like climbing and sinking.
+    // Scrolling changes the speed.
+    fn update(&mut self, input: &InputState, delta_seconds: f32, pose: &mut CameraPose) {
+        let pointer_delta = input.pointer_delta();
+        let max_pitch = MAX_PITCH_IN_DEGREES.to_radians();
+        self.yaw = Angle::from_radians(
+            self.yaw.radians
+                + self.turn_speed.radians
+                    * input.axis(Action::TurnRight, Action::TurnLeft)
+                    * delta_seconds
+                - self.pointer_sensitivity.radians * pointer_delta.x,
+        );
+        self.pitch = Angle::from_radians(
+            (self.pitch.radians
+                + self.turn_speed.radians
+                    * input.axis(Action::TurnDown, Action::TurnUp)
+                    * delta_seconds
+                - self.pointer_sensitivity.radians * pointer_delta.y)
+                .clamp(-max_pitch, max_pitch),
+        );
+        self.roll = Angle::from_radians(
+            self.roll.radians
+                + self.roll_speed.radians
+                    * input.axis(Action::RollRight, Action::RollLeft)
+                    * delta_seconds,
+        );
+        pose.set_yaw_pitch_roll(self.yaw, self.pitch, self.roll);
+
+        self.speed *= SCROLL_SPEED_FACTOR.powf(input.scroll_delta());
+
+        let direction = pose.forward() * input.axis(Action::MoveBackward, Action::MoveForward)
+            + pose.right() * input.axis(Action::MoveLeft, Action::MoveRight)
+            + pose.world_up() * input.axis(Action::MoveDown, Action::MoveUp);
+        if direction != WorldVector::ZERO {
+            pose.set_position(pose.position() + direction.normalize() * self.speed * delta_seconds);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use glamour::Vector2;
+
+// What the user wants to happen, independent of the key, button or stick that asked for it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    TurnLeft,
+    TurnRight,
+    TurnUp,
+    TurnDown,
+    RollLeft,
+    RollRight,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl Action {
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+// The input a controller sees for one frame: which actions are held, plus how far the pointer
+// was dragged and the wheel scrolled since the last `end_frame`. Filled from winit events by
+// `handle_window_event`, or by calling `set` for each button of a handheld.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InputState {
+    pressed: u32,
+    // In pixels, y pointing down
+    pointer_delta: Vector2<f32>,
+    // In lines, positive when scrolling away from the user
+    scroll_delta: f32,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, action: Action, pressed: bool) {
+        if pressed {
+            self.pressed |= action.bit();
+        } else {
+            self.pressed &= !action.bit();
+        }
+    }
+
+    pub fn press(&mut self, action: Action) {
+        self.set(action, true);
+    }
+
+    pub fn release(&mut self, action: Action) {
+        self.set(action, false);
+    }
+
+    // Lets go of everything, e.g. when the window loses focus and would miss the key releases
+    pub fn release_all(&mut self) {
+        self.pressed = 0;
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.pressed & action.bit() != 0
+    }
+
+    // -1, 0 or 1, cancelling out when both are held
+    pub fn axis(&self, negative: Action, positive: Action) -> f32 {
+        self.is_pressed(positive) as i32 as f32 - self.is_pressed(negative) as i32 as f32
+    }
+
+    pub fn add_pointer_motion(&mut self, delta: Vector2<f32>) {
+        self.pointer_delta += delta;
+    }
+
+    pub fn pointer_delta(&self) -> Vector2<f32> {
+        self.pointer_delta
+    }
+
+    pub fn add_scroll(&mut self, lines: f32) {
+        self.scroll_delta += lines;
+    }
+
+    pub fn scroll_delta(&self) -> f32 {
+        self.scroll_delta
+    }
+
+    // Only the held actions, for the updates of a frame after the one that took its deltas
+    pub fn held(&self) -> Self {
+        Self {
+            pressed: self.pressed,
+            ..Self::default()
+        }
+    }
+
+    // Has to be called once the frame's controllers have been updated, held actions stay
+    pub fn end_frame(&mut self) {
+        self.pointer_delta = Vector2::ZERO;
+        self.scroll_delta = 0.0;
+    }
+}
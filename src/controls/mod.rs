@@ -0,0 +1,5 @@
+pub mod fly;
+pub mod input;
+pub mod orbit;
+pub mod traits;
+pub mod winit_input;
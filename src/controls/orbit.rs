@@ -0,0 +1,121 @@
+use crate::common::camera::CameraPose;
+use crate::common::space::{WorldPoint, WorldVector};
+use crate::controls::input::{Action, InputState};
+use crate::controls::traits::CameraController;
+use glam::{Quat, Vec3};
+use glamour::Angle;
+use std::f32::consts::{PI, TAU};
+
+// Orbiting right over a pole would flip the view, so the elevation stops just short of it
+const MAX_ELEVATION_IN_DEGREES: f32 = 89.0;
+
+// Circles a target at a distance, e.g. to inspect a model. The camera's position is rebuilt from
+// these values on every update, so no error accumulates however long it orbits.
+pub struct OrbitController {
+    pub target: WorldPoint,
+    pub distance: f32,
+    // Around the world up, zero when looking down -Z in a Y-up world
+    pub azimuth: Angle,
+    // Above the target's horizon
+    pub elevation: Angle,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    // Per second while a turn action is held
+    pub turn_speed: Angle,
+    // Per second without any input, for turntable-style previews
+    pub auto_rotate_speed: Angle,
+    // Per pixel the pointer is dragged
+    pub pointer_sensitivity: Angle,
+    // The distance shrinks by a factor of e every 1 / `zoom_speed` seconds of zooming in
+    pub zoom_speed: f32,
+    // Fraction of the distance every wheel line zooms by
+    pub scroll_zoom_step: f32,
+}
+
+impl OrbitController {
+    pub fn new(target: WorldPoint, distance: f32) -> Self {
+        Self {
+            target,
+            distance,
+            azimuth: Angle::from_radians(0.0),
+            elevation: Angle::from_radians(0.0),
+            min_distance: 0.01,
+            max_distance: 1_000.0,
+            turn_speed: Angle::from_degrees(90.0),
+            auto_rotate_speed: Angle::from_radians(0.0),
+            pointer_sensitivity: Angle::from_degrees(0.3),
+            zoom_speed: 1.0,
+            scroll_zoom_step: 0.1,
+        }
+    }
+
+    // Picks up from wherever `pose` currently is, so that switching controllers does not jump
+    pub fn from_pose(pose: &CameraPose, target: WorldPoint) -> Self {
+        let offset = level_orientation(pose).inverse() * (pose.position() - target).to_raw();
+        let distance = offset.length();
+        let mut controller = Self::new(target, distance);
+        if distance > 0.0 {
+            controller.azimuth = Angle::from_radians(offset.x.atan2(offset.z));
+            controller.elevation =
+                Angle::from_radians((offset.y / distance).clamp(-1.0, 1.0).asin());
+        }
+
+        controller
+    }
+
+    // Moves `pose` to where the current values put it
+    pub fn apply(&mut self, pose: &mut CameraPose) {
+        let max_elevation = MAX_ELEVATION_IN_DEGREES.to_radians();
+        self.elevation =
+            Angle::from_radians(self.elevation.radians.clamp(-max_elevation, max_elevation));
+        self.azimuth = Angle::from_radians((self.azimuth.radians + PI).rem_euclid(TAU) - PI);
+        self.distance = self.distance.clamp(self.min_distance, self.max_distance);
+
+        let (azimuth_sin, azimuth_cos) = self.azimuth.radians.sin_cos();
+        let (elevation_sin, elevation_cos) = self.elevation.radians.sin_cos();
+        let offset = Vec3::new(
+            azimuth_sin * elevation_cos,
+            elevation_sin,
+            azimuth_cos * elevation_cos,
+        );
+
+        pose.set_position(
+            self.target + WorldVector::from_raw(level_orientation(pose) * offset * self.distance),
+        );
+        pose.look_at_point_with_up(&self.target, pose.world_up());
+    }
+}
+
+impl CameraController for OrbitController {
+    // The turn actions move the camera around the target, dragging turns the target along with
+    // the pointer and zooming or moving forward brings the camera closer
+    fn update(&mut self, input: &InputState, delta_seconds: f32, pose: &mut CameraPose) {
+        let pointer_delta = input.pointer_delta();
+        self.azimuth = Angle::from_radians(
+            self.azimuth.radians
+                + (self.turn_speed.radians * input.axis(Action::TurnLeft, Action::TurnRight)
+                    + self.auto_rotate_speed.radians)
+                    * delta_seconds
+                - self.pointer_sensitivity.radians * pointer_delta.x,
+        );
+        self.elevation = Angle::from_radians(
+            self.elevation.radians
+                + self.turn_speed.radians
+                    * input.axis(Action::TurnDown, Action::TurnUp)
+                    * delta_seconds
+                + self.pointer_sensitivity.radians * pointer_delta.y,
+        );
+
+        let zoom = input.axis(Action::ZoomOut, Action::ZoomIn)
+            + input.axis(Action::MoveBackward, Action::MoveForward);
+        self.distance *= (-self.zoom_speed * zoom * delta_seconds).exp()
+            * (1.0 - self.scroll_zoom_step).powf(input.scroll_delta());
+
+        self.apply(pose);
+    }
+}
+
+// Takes +Y to the pose's world up, orbits are computed as if the world were Y-up
+fn level_orientation(pose: &CameraPose) -> Quat {
+    Quat::from_rotation_arc(Vec3::Y, pose.world_up().to_raw())
+}
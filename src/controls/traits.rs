@@ -0,0 +1,9 @@
+use crate::common::camera::CameraPose;
+use crate::controls::input::InputState;
+
+// Moves a camera in response to input, works with any camera through its pose
+pub trait CameraController {
+    // Advances by `delta_seconds` of held actions. Pointer and scroll deltas are applied in full,
+    // so with several updates per frame they belong to only one of them, see `InputState::held`.
+    fn update(&mut self, input: &InputState, delta_seconds: f32, pose: &mut CameraPose);
+}
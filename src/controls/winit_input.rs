@@ -0,0 +1,110 @@
+use crate::controls::input::{Action, InputState};
+use glamour::Vector2;
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::{Key, NamedKey};
+
+// Pixels of a touchpad's scroll that count as one wheel line
+const PIXELS_PER_LINE: f32 = 20.0;
+
+// Keyboard and mouse bindings for desktop windows:
+// - W, A, S, D move, Space and C move up and down
+// - The arrow keys turn, Q and E roll
+// - +, - and the page keys zoom, as does the wheel
+// - Dragging with the left button turns
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WinitInput {
+    state: InputState,
+    dragging: bool,
+    cursor_position: Option<Vector2<f32>>,
+}
+
+impl WinitInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> &InputState {
+        &self.state
+    }
+
+    pub fn end_frame(&mut self) {
+        self.state.end_frame();
+    }
+
+    // Returns whether the event was one of the bindings
+    pub fn handle_window_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::KeyboardInput {
+                event: KeyEvent {
+                    logical_key, state, ..
+                },
+                ..
+            } => match key_action(logical_key) {
+                Some(action) => {
+                    self.state.set(action, *state == ElementState::Pressed);
+                    true
+                }
+                None => false,
+            },
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                self.dragging = *state == ElementState::Pressed;
+                true
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = Vector2::new(position.x as f32, position.y as f32);
+                if let (true, Some(previous)) = (self.dragging, self.cursor_position) {
+                    self.state.add_pointer_motion(position - previous);
+                }
+                self.cursor_position = Some(position);
+                self.dragging
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor_position = None;
+                false
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.state.add_scroll(match delta {
+                    MouseScrollDelta::LineDelta(_, lines) => *lines,
+                    MouseScrollDelta::PixelDelta(pixels) => pixels.y as f32 / PIXELS_PER_LINE,
+                });
+                true
+            }
+            // Releases would go to another window
+            WindowEvent::Focused(false) => {
+                self.state.release_all();
+                self.dragging = false;
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+pub fn key_action(key: &Key) -> Option<Action> {
+    match key {
+        Key::Named(NamedKey::ArrowLeft) => Some(Action::TurnLeft),
+        Key::Named(NamedKey::ArrowRight) => Some(Action::TurnRight),
+        Key::Named(NamedKey::ArrowUp) => Some(Action::TurnUp),
+        Key::Named(NamedKey::ArrowDown) => Some(Action::TurnDown),
+        Key::Named(NamedKey::Space) => Some(Action::MoveUp),
+        Key::Named(NamedKey::PageUp) => Some(Action::ZoomIn),
+        Key::Named(NamedKey::PageDown) => Some(Action::ZoomOut),
+        Key::Character(character) => match character.to_lowercase().as_str() {
+            "w" => Some(Action::MoveForward),
+            "s" => Some(Action::MoveBackward),
+            "a" => Some(Action::MoveLeft),
+            "d" => Some(Action::MoveRight),
+            "c" => Some(Action::MoveDown),
+            "q" => Some(Action::RollLeft),
+            "e" => Some(Action::RollRight),
+            "+" | "=" => Some(Action::ZoomIn),
+            "-" => Some(Action::ZoomOut),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -1,6 +1,10 @@
 pub mod buffers;
 pub mod common;
+pub mod controls;
 pub mod lights;
 pub mod objects;
+pub mod pipeline;
+pub mod present;
 pub mod shaders;
+pub mod textures;
 pub mod utils;
@@ -1,5 +1,31 @@
+use crate::common::space::{WorldPoint, WorldVector};
 use crate::lights::traits::*;
+use palette::LinSrgb;
 
-pub struct DirectionalLight;
+// An infinitely distant light, e.g. the sun, that reaches every point from the same direction
+#[derive(Clone, Copy, Debug)]
+pub struct DirectionalLight {
+    // The direction the light travels in
+    pub direction: WorldVector,
+    pub color: LinSrgb,
+    pub intensity: f32,
+}
 
-impl LightSource for DirectionalLight {}
+impl DirectionalLight {
+    pub fn new(direction: WorldVector, color: LinSrgb, intensity: f32) -> Self {
+        Self {
+            direction: direction.normalize(),
+            color,
+            intensity,
+        }
+    }
+}
+
+impl LightSource for DirectionalLight {
+    fn incident_light(&self, _point: WorldPoint) -> IncidentLight {
+        IncidentLight {
+            direction: -self.direction.normalize_or_zero(),
+            radiance: self.color * self.intensity,
+        }
+    }
+}
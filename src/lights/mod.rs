@@ -1,4 +1,5 @@
 pub mod directional_light;
 pub mod positional_light;
+pub mod shadow_map;
 pub mod spotlight;
-mod traits;
+pub mod traits;
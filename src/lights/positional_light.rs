@@ -1,5 +1,47 @@
+use crate::common::space::WorldPoint;
+use crate::common::traits::Positionable;
 use crate::lights::traits::*;
+use palette::LinSrgb;
 
-pub struct PositionalLight {}
+// A point light shining equally in all directions
+#[derive(Clone, Copy, Debug)]
+pub struct PositionalLight {
+    pub position: WorldPoint,
+    pub color: LinSrgb,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+}
 
-impl LightSource for PositionalLight {}
+impl PositionalLight {
+    pub fn new(
+        position: WorldPoint,
+        color: LinSrgb,
+        intensity: f32,
+        attenuation: Attenuation,
+    ) -> Self {
+        Self {
+            position,
+            color,
+            intensity,
+            attenuation,
+        }
+    }
+}
+
+impl LightSource for PositionalLight {
+    fn incident_light(&self, point: WorldPoint) -> IncidentLight {
+        let to_light = self.position - point;
+        let distance = to_light.length();
+
+        IncidentLight {
+            direction: to_light.normalize_or_zero(),
+            radiance: self.color * (self.intensity * self.attenuation.factor(distance)),
+        }
+    }
+}
+
+impl Positionable for PositionalLight {
+    fn get_position(&self) -> WorldPoint {
+        self.position
+    }
+}
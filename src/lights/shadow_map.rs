@@ -0,0 +1,257 @@
+use crate::buffers::depth::DepthBuffer;
+use crate::common::camera::{orthographic_projection, perspective_projection, CameraPose};
+use crate::common::space::{
+    to_homogeneous_clip_point, ScreenPoint, ViewPoint, WorldBox, WorldPoint, WorldToClipTransform,
+    WorldToViewTransform, WorldVector,
+};
+use crate::lights::directional_light::DirectionalLight;
+use crate::lights::spotlight::Spotlight;
+use crate::lights::traits::*;
+use crate::shaders::vertex::ClipVertex;
+use crate::shaders::vertex_post_processing::{FaceCulling, Viewport};
+use glamour::Vector2;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShadowFilter {
+    // A single shadow map texel decides, giving hard, aliased edges
+    #[default]
+    Hard,
+    // Percentage-closer filtering, averages the test over a (2 * radius + 1)² texel square
+    Pcf {
+        radius: u32,
+    },
+}
+
+// The scene's depth as seen from a light, rendered by a shadow pass
+pub struct ShadowMap {
+    depth_buffer: DepthBuffer,
+    viewport: Viewport,
+    world_to_clip: WorldToClipTransform,
+    // Subtracted from a point's depth before the test, in the map's [0, 1] depth range, to keep
+    // surfaces from shadowing themselves
+    pub bias: f32,
+    // Pushes casters back by this many texels' worth of their depth slope as they are rendered,
+    // surfaces facing away from the light need much more bias than those facing it
+    pub slope_bias: f32,
+    pub filter: ShadowFilter,
+    // Casters are rendered double-sided by default so that open meshes still cast shadows
+    pub face_culling: FaceCulling,
+}
+
+impl ShadowMap {
+    pub fn new(dimensions: Vector2<u32>) -> Self {
+        Self {
+            depth_buffer: DepthBuffer::new(dimensions),
+            viewport: Viewport::new(dimensions),
+            world_to_clip: WorldToClipTransform::IDENTITY,
+            bias: 0.002,
+            slope_bias: 2.0,
+            filter: ShadowFilter::default(),
+            face_culling: FaceCulling::None,
+        }
+    }
+
+    pub fn dimensions(&self) -> Vector2<u32> {
+        self.depth_buffer.dimensions()
+    }
+
+    pub fn depth_buffer(&self) -> &DepthBuffer {
+        &self.depth_buffer
+    }
+
+    pub(crate) fn depth_buffer_mut(&mut self) -> &mut DepthBuffer {
+        &mut self.depth_buffer
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    pub fn world_to_clip(&self) -> WorldToClipTransform {
+        self.world_to_clip
+    }
+
+    pub fn set_world_to_clip(&mut self, world_to_clip: WorldToClipTransform) {
+        self.world_to_clip = world_to_clip;
+    }
+
+    // 1.0 if the light reaches `point`, 0.0 if it is shadowed, fractions in between with PCF.
+    // Points the map does not cover count as lit.
+    pub fn visibility(&self, point: WorldPoint) -> f32 {
+        let position = to_homogeneous_clip_point(&self.world_to_clip, point);
+        if position.w <= 0.0 {
+            return 1.0;
+        }
+
+        let screen_vertex = self.viewport.to_screen(&ClipVertex {
+            position,
+            varyings: (),
+        });
+        let dimensions = self.dimensions();
+        let (x, y) = (
+            screen_vertex.position.x.floor(),
+            screen_vertex.position.y.floor(),
+        );
+        if x < 0.0
+            || y < 0.0
+            || x >= dimensions.x as f32
+            || y >= dimensions.y as f32
+            || screen_vertex.depth > DepthBuffer::FAR_DEPTH
+        {
+            return 1.0;
+        }
+
+        let depth = screen_vertex.depth - self.bias;
+        let (x, y) = (x as i64, y as i64);
+        match self.filter {
+            ShadowFilter::Hard => self.lit(x, y, depth),
+            ShadowFilter::Pcf { radius } => {
+                let radius = radius as i64;
+                let mut visibility = 0.0;
+                for offset_y in -radius..=radius {
+                    for offset_x in -radius..=radius {
+                        visibility += self.lit(x + offset_x, y + offset_y, depth);
+                    }
+                }
+
+                visibility / ((2 * radius + 1) * (2 * radius + 1)) as f32
+            }
+        }
+    }
+
+    // Depths of a caster triangle pushed back by the slope bias
+    pub(crate) fn offset_depths(&self, triangle: &[ScreenPoint; 3], depths: [f32; 3]) -> [f32; 3] {
+        let edge_1 = triangle[1] - triangle[0];
+        let edge_2 = triangle[2] - triangle[0];
+        let (depth_delta_1, depth_delta_2) = (depths[1] - depths[0], depths[2] - depths[0]);
+        let determinant = edge_1.x * edge_2.y - edge_1.y * edge_2.x;
+        if determinant == 0.0 {
+            return depths;
+        }
+
+        let depth_slope_x = (depth_delta_1 * edge_2.y - depth_delta_2 * edge_1.y) / determinant;
+        let depth_slope_y = (depth_delta_2 * edge_1.x - depth_delta_1 * edge_2.x) / determinant;
+        let offset = self.slope_bias * depth_slope_x.abs().max(depth_slope_y.abs());
+
+        depths.map(|depth| depth + offset)
+    }
+
+    // Texels past the edges are clamped, so that filtering does not darken the map's border
+    fn lit(&self, x: i64, y: i64, depth: f32) -> f32 {
+        let dimensions = self.dimensions();
+        let x = x.clamp(0, dimensions.x as i64 - 1) as u32;
+        let y = y.clamp(0, dimensions.y as i64 - 1) as u32;
+        match self.depth_buffer.get_depth(x, y) {
+            Some(stored_depth) if depth > stored_depth => 0.0,
+            _ => 1.0,
+        }
+    }
+}
+
+// A light that can render a shadow map
+pub trait ShadowCaster: LightSource {
+    // A projection from the light's point of view that covers everything inside `scene_bounds`
+    fn light_to_clip(&self, scene_bounds: WorldBox) -> WorldToClipTransform;
+}
+
+impl ShadowCaster for DirectionalLight {
+    // Orthographic, fitted tightly around the scene
+    fn light_to_clip(&self, scene_bounds: WorldBox) -> WorldToClipTransform {
+        let center = scene_bounds.min + (scene_bounds.max - scene_bounds.min) / 2.0;
+        let world_to_view = light_view_transform(center, self.direction);
+
+        let mut view_min = ViewPoint::splat(f32::MAX);
+        let mut view_max = ViewPoint::splat(f32::MIN);
+        for corner in box_corners(scene_bounds) {
+            let view_corner = world_to_view.map_point(corner);
+            view_min = view_min.min(view_corner);
+            view_max = view_max.max(view_corner);
+        }
+
+        // Keeps flat scenes from collapsing the projection
+        let padding = (view_max - view_min).length().max(f32::EPSILON) * 0.01;
+        let view_to_clip = orthographic_projection(
+            view_min.x - padding,
+            view_max.x + padding,
+            view_min.y - padding,
+            view_max.y + padding,
+            -view_max.z - padding,
+            -view_min.z + padding,
+        );
+
+        world_to_view.then(view_to_clip)
+    }
+}
+
+impl ShadowCaster for Spotlight {
+    // Perspective, covering the outer cone and clipped to the scene's depth range
+    fn light_to_clip(&self, scene_bounds: WorldBox) -> WorldToClipTransform {
+        let world_to_view = light_view_transform(self.position, self.direction);
+
+        let corner_depths =
+            box_corners(scene_bounds).map(|corner| -world_to_view.map_point(corner).z);
+        let far_plane = corner_depths.into_iter().fold(f32::EPSILON, f32::max);
+        // Scenes surrounding the light would otherwise get a near plane behind it
+        let near_plane = corner_depths
+            .into_iter()
+            .fold(far_plane, f32::min)
+            .max(far_plane * 0.001);
+        let field_of_view = (self.outer_cone.to_degrees() * 2.0).min(179.0);
+
+        world_to_view.then(perspective_projection(
+            field_of_view,
+            1.0,
+            near_plane,
+            far_plane * 1.01,
+        ))
+    }
+}
+
+// Attenuates a light by its shadow map, shading models see it like any other light
+pub struct ShadowedLight<'a, L: LightSource> {
+    pub light: &'a L,
+    pub shadow_map: &'a ShadowMap,
+}
+
+impl<'a, L: LightSource> ShadowedLight<'a, L> {
+    pub fn new(light: &'a L, shadow_map: &'a ShadowMap) -> Self {
+        Self { light, shadow_map }
+    }
+}
+
+impl<L: LightSource> LightSource for ShadowedLight<'_, L> {
+    fn incident_light(&self, point: WorldPoint) -> IncidentLight {
+        let incident = self.light.incident_light(point);
+        IncidentLight {
+            radiance: incident.radiance * self.shadow_map.visibility(point),
+            ..incident
+        }
+    }
+}
+
+// Looks along `forward` from `position`, lights have no roll so any up vector will do
+fn light_view_transform(position: WorldPoint, forward: WorldVector) -> WorldToViewTransform {
+    CameraPose::new(position, forward).view_matrix()
+}
+
+fn box_corners(bounds: WorldBox) -> [WorldPoint; 8] {
+    [0, 1, 2, 3, 4, 5, 6, 7].map(|corner_idx| {
+        WorldPoint::new(
+            if corner_idx & 1 == 0 {
+                bounds.min.x
+            } else {
+                bounds.max.x
+            },
+            if corner_idx & 2 == 0 {
+                bounds.min.y
+            } else {
+                bounds.max.y
+            },
+            if corner_idx & 4 == 0 {
+                bounds.min.z
+            } else {
+                bounds.max.z
+            },
+        )
+    })
+}
@@ -1,5 +1,74 @@
+use crate::common::space::{WorldPoint, WorldVector};
+use crate::common::traits::Positionable;
 use crate::lights::traits::*;
+use glamour::Angle;
+use palette::LinSrgb;
 
-pub struct Spotlight;
+// A point light restricted to a cone. Full intensity inside the inner cone fades smoothly to
+// nothing at the outer cone, both angles are measured from the cone's axis.
+#[derive(Clone, Copy, Debug)]
+pub struct Spotlight {
+    pub position: WorldPoint,
+    // The cone's axis, pointing away from the light
+    pub direction: WorldVector,
+    pub color: LinSrgb,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+    pub inner_cone: Angle,
+    pub outer_cone: Angle,
+}
 
-impl LightSource for Spotlight {}
+impl Spotlight {
+    pub fn new(
+        position: WorldPoint,
+        direction: WorldVector,
+        color: LinSrgb,
+        intensity: f32,
+        attenuation: Attenuation,
+        inner_cone: Angle,
+        outer_cone: Angle,
+    ) -> Self {
+        Self {
+            position,
+            direction: direction.normalize(),
+            color,
+            intensity,
+            attenuation,
+            inner_cone,
+            outer_cone,
+        }
+    }
+
+    // 1.0 inside the inner cone, 0.0 outside the outer one, smoothstep in between
+    pub fn cone_factor(&self, direction_from_light: WorldVector) -> f32 {
+        let cos_angle = self.direction.normalize_or_zero().dot(direction_from_light);
+        let cos_inner = self.inner_cone.cos();
+        let cos_outer = self.outer_cone.cos();
+        if cos_inner <= cos_outer {
+            return if cos_angle >= cos_outer { 1.0 } else { 0.0 };
+        }
+
+        let t = ((cos_angle - cos_outer) / (cos_inner - cos_outer)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl LightSource for Spotlight {
+    fn incident_light(&self, point: WorldPoint) -> IncidentLight {
+        let to_light = self.position - point;
+        let distance = to_light.length();
+        let direction = to_light.normalize_or_zero();
+        let falloff = self.attenuation.factor(distance) * self.cone_factor(-direction);
+
+        IncidentLight {
+            direction,
+            radiance: self.color * (self.intensity * falloff),
+        }
+    }
+}
+
+impl Positionable for Spotlight {
+    fn get_position(&self) -> WorldPoint {
+        self.position
+    }
+}
@@ -1 +1,59 @@
-pub trait LightSource {}
+use crate::common::space::{WorldPoint, WorldVector};
+use palette::LinSrgb;
+
+// The light arriving at a point from a single light source
+#[derive(Clone, Copy, Debug)]
+pub struct IncidentLight {
+    // Unit vector pointing from the lit point towards the light
+    pub direction: WorldVector,
+    // Black when the point is outside the light's reach
+    pub radiance: LinSrgb,
+}
+
+pub trait LightSource {
+    fn incident_light(&self, point: WorldPoint) -> IncidentLight;
+}
+
+// Divides a light's intensity by `constant + linear * d + quadratic * d²` at distance `d`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl Default for Attenuation {
+    // Physically based inverse-square falloff
+    fn default() -> Self {
+        Self {
+            constant: 1.0,
+            linear: 0.0,
+            quadratic: 1.0,
+        }
+    }
+}
+
+impl Attenuation {
+    pub fn new(constant: f32, linear: f32, quadratic: f32) -> Self {
+        Self {
+            constant,
+            linear,
+            quadratic,
+        }
+    }
+
+    // No falloff at all
+    pub fn none() -> Self {
+        Self::new(1.0, 0.0, 0.0)
+    }
+
+    pub fn factor(&self, distance: f32) -> f32 {
+        let denominator =
+            self.constant + self.linear * distance + self.quadratic * distance * distance;
+        if denominator > 0.0 {
+            denominator.recip()
+        } else {
+            0.0
+        }
+    }
+}
@@ -1,15 +1,50 @@
-use crate::common::space::{ModelBox, ModelPoint};
+use crate::common::space::{ModelBox, ModelPoint, ModelVector, TexturePoint};
+use crate::objects::triangulation::{triangulate, TriangulationError};
 use derive_more::Constructor;
 use obj::raw::object::Polygon;
 use obj::raw::RawObj;
-use obj::ObjError;
+use obj::{LoadError, LoadErrorKind, ObjError};
+use std::collections::HashMap;
 use std::io::BufRead;
 
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MeshVertex {
+    pub position: ModelPoint,
+    pub normal: ModelVector,
+    pub texture_coordinates: TexturePoint,
+}
+
+// An OBJ face that was left out of the mesh
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UntriangulatedFace {
+    pub polygon_index: usize,
+    pub error: TriangulationError,
+}
+
+// Indices of one face corner into the position, texture coordinate and normal lists of an OBJ
+type ObjCorner = (usize, Option<usize>, Option<usize>);
+
 #[derive(Constructor, Default)]
 pub struct Mesh {
-    pub vertices: Vec<ModelPoint>,
+    pub vertices: Vec<MeshVertex>,
     tris_face_indices: Vec<[usize; 3]>,
     bounding_box: ModelBox,
+    untriangulated_faces: Vec<UntriangulatedFace>,
+}
+
+// The parser checks indices against what it has read so far, this keeps a malformed file from
+// panicking even if it lets one through
+fn index_out_of_range(message: &'static str) -> ObjError {
+    ObjError::Load(LoadError::new(LoadErrorKind::IndexOutOfRange, message))
+}
+
+fn position(raw_obj: &RawObj, pos: usize) -> Result<ModelPoint, ObjError> {
+    let &(x, y, z, _) = raw_obj
+        .positions
+        .get(pos)
+        .ok_or_else(|| index_out_of_range("position index out of range"))?;
+
+    Ok(ModelPoint::new(x, y, z))
 }
 
 impl Mesh {
@@ -22,40 +57,147 @@ impl Mesh {
                 min: ModelPoint::new(f32::MAX, f32::MAX, f32::MAX),
                 max: ModelPoint::new(f32::MIN, f32::MIN, f32::MIN),
             },
+            untriangulated_faces: vec![],
         };
 
-        mesh.vertices = raw_obj
-            .positions
-            .iter()
-            .map(|&(x, y, z, _)| ModelPoint::new(x, y, z))
-            .collect();
+        let mut tris_face_corners: Vec<[ObjCorner; 3]> = vec![];
+        for (polygon_index, polygon) in raw_obj.polygons.iter().enumerate() {
+            let corners: Vec<ObjCorner> = match polygon {
+                Polygon::P(indices) => indices.iter().map(|&pos| (pos, None, None)).collect(),
+                Polygon::PT(indices) => indices
+                    .iter()
+                    .map(|&(pos, tex)| (pos, Some(tex), None))
+                    .collect(),
+                Polygon::PN(indices) => indices
+                    .iter()
+                    .map(|&(pos, norm)| (pos, None, Some(norm)))
+                    .collect(),
+                Polygon::PTN(indices) => indices
+                    .iter()
+                    .map(|&(pos, tex, norm)| (pos, Some(tex), Some(norm)))
+                    .collect(),
+            };
+            let positions = corners
+                .iter()
+                .map(|&(pos, _, _)| position(&raw_obj, pos))
+                .collect::<Result<Vec<_>, _>>()?;
 
-        mesh.tris_face_indices = raw_obj
-            .polygons
-            .iter()
-            .flat_map(|polygon| {
-                let indices: Vec<usize> = match polygon {
-                    Polygon::P(indices) => indices.to_vec(),
-                    Polygon::PT(indices) => indices.iter().map(|&(pos, _)| pos).collect(),
-                    Polygon::PN(indices) => indices.iter().map(|&(pos, _)| pos).collect(),
-                    Polygon::PTN(indices) => indices.iter().map(|&(pos, _, _)| pos).collect(),
+            match triangulate(&positions) {
+                Ok(triangles) => tris_face_corners.extend(
+                    triangles
+                        .into_iter()
+                        .map(|triangle| triangle.map(|corner_idx| corners[corner_idx])),
+                ),
+                Err(error) => mesh.untriangulated_faces.push(UntriangulatedFace {
+                    polygon_index,
+                    error,
+                }),
+            }
+        }
+
+        // Corners without a normal get the averaged normal of all faces sharing their position
+        let smooth_normals = Self::calculate_smooth_normals(&raw_obj, &tris_face_corners)?;
+
+        // OBJ indexes positions, texture coordinates and normals separately, every distinct
+        // combination of them becomes one vertex
+        let mut vertex_indices: HashMap<ObjCorner, usize> = HashMap::new();
+        for face_corners in &tris_face_corners {
+            let mut face_indices = [0; 3];
+            for (face_index, &corner) in face_indices.iter_mut().zip(face_corners) {
+                if let Some(&vertex_index) = vertex_indices.get(&corner) {
+                    *face_index = vertex_index;
+                    continue;
+                }
+
+                let (pos, tex, norm) = corner;
+                let normal = match norm {
+                    Some(norm) => {
+                        let &(x, y, z) = raw_obj
+                            .normals
+                            .get(norm)
+                            .ok_or_else(|| index_out_of_range("normal index out of range"))?;
+                        ModelVector::new(x, y, z).normalize_or_zero()
+                    }
+                    None => smooth_normals[pos],
                 };
-                indices
-                    .chunks_exact(3)
-                    .map(|chunk| [chunk[0], chunk[1], chunk[2]])
-                    .collect::<Vec<_>>()
-            })
-            .collect();
+                let texture_coordinates = match tex {
+                    Some(tex) => {
+                        let &(u, v, _) = raw_obj.tex_coords.get(tex).ok_or_else(|| {
+                            index_out_of_range("texture coordinate index out of range")
+                        })?;
+                        TexturePoint::new(u, v)
+                    }
+                    None => TexturePoint::default(),
+                };
+
+                mesh.vertices.push(MeshVertex {
+                    position: position(&raw_obj, pos)?,
+                    normal,
+                    texture_coordinates,
+                });
+                *face_index = mesh.vertices.len() - 1;
+                vertex_indices.insert(corner, *face_index);
+            }
+            mesh.tris_face_indices.push(face_indices);
+        }
 
         for vertex in &mesh.vertices {
-            mesh.bounding_box.min = mesh.bounding_box.min.min(*vertex);
-            mesh.bounding_box.max = mesh.bounding_box.max.max(*vertex);
+            mesh.bounding_box.min = mesh.bounding_box.min.min(vertex.position);
+            mesh.bounding_box.max = mesh.bounding_box.max.max(vertex.position);
         }
 
         Ok(mesh)
     }
 
+    fn calculate_smooth_normals(
+        raw_obj: &RawObj,
+        tris_face_corners: &[[ObjCorner; 3]],
+    ) -> Result<Vec<ModelVector>, ObjError> {
+        let mut normals = vec![ModelVector::ZERO; raw_obj.positions.len()];
+
+        for face_corners in tris_face_corners {
+            let [a, b, c] = [
+                position(raw_obj, face_corners[0].0)?,
+                position(raw_obj, face_corners[1].0)?,
+                position(raw_obj, face_corners[2].0)?,
+            ];
+            // Not normalized, so that bigger faces weigh more
+            let face_normal = (b - a).cross(c - a);
+            for (pos, _, _) in face_corners {
+                normals[*pos] += face_normal;
+            }
+        }
+
+        Ok(normals
+            .into_iter()
+            .map(|normal| normal.normalize_or_zero())
+            .collect())
+    }
+
+    // Faces of the source file that could not be triangulated and are missing from the mesh
+    pub fn untriangulated_faces(&self) -> &[UntriangulatedFace] {
+        &self.untriangulated_faces
+    }
+
+    pub fn bounding_box(&self) -> ModelBox {
+        self.bounding_box
+    }
+
+    pub fn tris_face_indices(&self) -> &[[usize; 3]] {
+        &self.tris_face_indices
+    }
+
     pub fn tris_faces(&self) -> impl Iterator<Item = [ModelPoint; 3]> + '_ {
+        self.tris_face_indices.iter().map(|&[a, b, c]| {
+            [
+                self.vertices[a].position,
+                self.vertices[b].position,
+                self.vertices[c].position,
+            ]
+        })
+    }
+
+    pub fn tris_face_vertices(&self) -> impl Iterator<Item = [MeshVertex; 3]> + '_ {
         self.tris_face_indices
             .iter()
             .map(|&[a, b, c]| [self.vertices[a], self.vertices[b], self.vertices[c]])
@@ -1,3 +1,4 @@
 pub mod mesh;
 pub mod object;
 pub mod traits;
+pub mod triangulation;
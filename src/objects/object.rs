@@ -1,12 +1,177 @@
-use crate::common::space::{ModelScalar, WorldPoint, WorldScalar, WorldVector};
-use crate::common::traits::Positionable;
+use crate::common::space::{
+    to_world_box, ModelPoint, ModelToWorldTransform, WorldBox, WorldPoint, WorldSize, WorldVector,
+};
+use crate::common::traits::{Bounded, Dimensionable, Positionable};
+use crate::objects::mesh::Mesh;
+use glam::Quat;
+use glamour::{Angle, Matrix4};
+use std::rc::Rc;
 
-struct Object {
+// A scene node, its transform is relative to its parent
+pub struct Object {
+    mesh: Option<Rc<Mesh>>,
+    translation: WorldVector,
+    rotation: Quat,
     scale: WorldVector,
+    children: Vec<Object>,
+    world_transform: ModelToWorldTransform,
+    // Set when the local transform changes, until the world transforms are updated again
+    world_transform_stale: bool,
+}
+
+impl Object {
+    pub fn new(mesh: Rc<Mesh>) -> Self {
+        Self {
+            mesh: Some(mesh),
+            ..Self::empty()
+        }
+    }
+
+    // A node without a mesh, useful for grouping other nodes
+    pub fn empty() -> Self {
+        Self {
+            mesh: None,
+            translation: WorldVector::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: WorldVector::ONE,
+            children: vec![],
+            world_transform: ModelToWorldTransform::IDENTITY,
+            world_transform_stale: false,
+        }
+    }
+
+    pub fn mesh(&self) -> Option<&Rc<Mesh>> {
+        self.mesh.as_ref()
+    }
+
+    pub fn set_mesh(&mut self, mesh: Option<Rc<Mesh>>) {
+        self.mesh = mesh;
+    }
+
+    pub fn translation(&self) -> WorldVector {
+        self.translation
+    }
+
+    // Like the other transform setters, takes effect once `update_world_transforms` is called on
+    // the root
+    pub fn set_translation(&mut self, translation: WorldVector) {
+        self.translation = translation;
+        self.world_transform_stale = true;
+    }
+
+    pub fn rotation(&self) -> Quat {
+        self.rotation
+    }
+
+    pub fn set_rotation(&mut self, rotation: Quat) {
+        self.rotation = rotation.normalize();
+        self.world_transform_stale = true;
+    }
+
+    pub fn rotate(&mut self, axis: WorldVector, angle: Angle) {
+        self.set_rotation(
+            Quat::from_axis_angle(axis.normalize().into(), angle.radians) * self.rotation,
+        );
+    }
+
+    pub fn scale(&self) -> WorldVector {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: WorldVector) {
+        self.scale = scale;
+        self.world_transform_stale = true;
+    }
+
+    pub fn children(&self) -> &[Object] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut [Object] {
+        &mut self.children
+    }
+
+    // The child's world transform is stale until the next update, it has a new parent
+    pub fn add_child(&mut self, mut child: Object) -> &mut Object {
+        child.world_transform_stale = true;
+        self.children.push(child);
+        self.children.last_mut().unwrap()
+    }
+
+    pub fn local_transform(&self) -> ModelToWorldTransform {
+        ModelToWorldTransform::from_matrix_unchecked(Matrix4::from_scale_rotation_translation(
+            self.scale.to_untyped(),
+            self.rotation,
+            self.translation.to_untyped(),
+        ))
+    }
+
+    // As of the last `update_world_transforms` on the root of the hierarchy, moves since then
+    // are not reflected yet. Drawing a moved object without an update panics in debug builds.
+    pub fn world_transform(&self) -> ModelToWorldTransform {
+        self.world_transform
+    }
+
+    // Whether this node or any of its descendants has been moved since the last update
+    pub fn needs_world_transform_update(&self) -> bool {
+        self.world_transform_stale
+            || self
+                .children
+                .iter()
+                .any(Object::needs_world_transform_update)
+    }
+
+    // Has to be called on the root node whenever a transform in the hierarchy changes
+    pub fn update_world_transforms(&mut self) {
+        self.propagate_world_transform(&ModelToWorldTransform::IDENTITY);
+    }
+
+    fn propagate_world_transform(&mut self, parent_world_transform: &ModelToWorldTransform) {
+        self.world_transform = ModelToWorldTransform::from_matrix_unchecked(
+            parent_world_transform.matrix * self.local_transform().matrix,
+        );
+        self.world_transform_stale = false;
+
+        let world_transform = self.world_transform;
+        for child in &mut self.children {
+            child.propagate_world_transform(&world_transform);
+        }
+    }
+
+    // Depth-first walk over this node and all of its descendants
+    pub fn visit<F: FnMut(&Object)>(&self, f: &mut F) {
+        f(self);
+        for child in &self.children {
+            child.visit(f);
+        }
+    }
 }
 
 impl Positionable for Object {
     fn get_position(&self) -> WorldPoint {
-        todo!()
+        self.world_transform().map_point(ModelPoint::ZERO)
+    }
+
+    fn as_bounded(&self) -> Option<&dyn Bounded> {
+        self.mesh.as_ref().map(|_| self as &dyn Bounded)
+    }
+}
+
+impl Dimensionable for Object {
+    fn get_dimensions(&self) -> WorldSize {
+        let bounding_box = self.calculate_bounding_box();
+        WorldSize::from_vector(bounding_box.max - bounding_box.min)
+    }
+}
+
+impl Bounded for Object {
+    // Encloses the mesh's box however the object is rotated
+    fn calculate_bounding_box(&self) -> WorldBox {
+        let Some(mesh) = &self.mesh else {
+            let position = self.get_position();
+            return WorldBox::new(position, position);
+        };
+
+        to_world_box(&self.world_transform(), &mesh.bounding_box())
     }
 }
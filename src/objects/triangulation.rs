@@ -0,0 +1,156 @@
+use crate::common::space::{ModelPoint, ModelScalar, ModelVector};
+use derive_more::Display;
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum TriangulationError {
+    #[display("polygon has fewer than three vertices")]
+    TooFewVertices,
+    #[display("polygon has no area")]
+    Degenerate,
+    #[display("polygon is self-intersecting or not planar enough to find an ear")]
+    NoEarFound,
+}
+
+type ProjectedPoint = (ModelScalar, ModelScalar);
+
+// Relative to the polygon's size, so that both tiny and huge models behave the same
+const RELATIVE_EPSILON: ModelScalar = 1e-6;
+
+// Splits a polygon into triangles wound the same way as the polygon, returned as indices into
+// `points`. Polygons that fan out from their first corner, e.g. convex ones, become a fan, others
+// are ear-clipped.
+pub fn triangulate(points: &[ModelPoint]) -> Result<Vec<[usize; 3]>, TriangulationError> {
+    if points.len() < 3 {
+        return Err(TriangulationError::TooFewVertices);
+    }
+
+    let normal = newell_normal(points);
+    let size = points
+        .iter()
+        .map(|point| (*point - points[0]).length())
+        .fold(0.0, ModelScalar::max);
+    let epsilon = RELATIVE_EPSILON * size * size;
+    if normal.length() <= epsilon {
+        return Err(TriangulationError::Degenerate);
+    }
+
+    if points.len() == 3 {
+        return Ok(vec![[0, 1, 2]]);
+    }
+
+    let projected_points = project_onto_dominant_plane(points, normal);
+    // Looking at the fan's triangles rather than the polygon's corners, as a corner repeated or
+    // along an edge hides whether the polygon turns there
+    let fan: Vec<[usize; 3]> = (1..points.len() - 1).map(|idx| [0, idx, idx + 1]).collect();
+    let is_fan = fan.iter().all(|&[a, b, c]| {
+        cross_2d(
+            projected_points[a],
+            projected_points[b],
+            projected_points[c],
+        ) >= -epsilon
+    });
+
+    if is_fan {
+        Ok(fan)
+    } else {
+        clip_ears(&projected_points, epsilon)
+    }
+}
+
+// Newell's method, robust for concave and slightly non-planar polygons. The length is twice the
+// polygon's area.
+fn newell_normal(points: &[ModelPoint]) -> ModelVector {
+    let mut normal = ModelVector::ZERO;
+    for (idx, current) in points.iter().enumerate() {
+        let next = points[(idx + 1) % points.len()];
+        normal.x += (current.y - next.y) * (current.z + next.z);
+        normal.y += (current.z - next.z) * (current.x + next.x);
+        normal.z += (current.x - next.x) * (current.y + next.y);
+    }
+
+    normal
+}
+
+// Drops the coordinate along which the normal is largest. The remaining two are ordered so that
+// counter-clockwise around the normal stays counter-clockwise in 2D.
+fn project_onto_dominant_plane(points: &[ModelPoint], normal: ModelVector) -> Vec<ProjectedPoint> {
+    let abs_normal = normal.abs();
+    let (project, sign): (fn(&ModelPoint) -> ProjectedPoint, ModelScalar) =
+        if abs_normal.z >= abs_normal.x && abs_normal.z >= abs_normal.y {
+            (|point| (point.x, point.y), normal.z.signum())
+        } else if abs_normal.x >= abs_normal.y {
+            (|point| (point.y, point.z), normal.x.signum())
+        } else {
+            (|point| (point.z, point.x), normal.y.signum())
+        };
+
+    points
+        .iter()
+        .map(project)
+        .map(|(a, b)| (a, b * sign))
+        .collect()
+}
+
+fn cross_2d(a: ProjectedPoint, b: ProjectedPoint, c: ProjectedPoint) -> ModelScalar {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn is_inside_triangle(point: ProjectedPoint, triangle: [ProjectedPoint; 3]) -> bool {
+    cross_2d(triangle[0], triangle[1], point) >= 0.0
+        && cross_2d(triangle[1], triangle[2], point) >= 0.0
+        && cross_2d(triangle[2], triangle[0], point) >= 0.0
+}
+
+// Expects a counter-clockwise polygon, which the projection guarantees
+fn clip_ears(
+    points: &[ProjectedPoint],
+    epsilon: ModelScalar,
+) -> Result<Vec<[usize; 3]>, TriangulationError> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity(points.len() - 2);
+
+    while remaining.len() > 3 {
+        let corner = |idx: usize| {
+            [
+                remaining[(idx + remaining.len() - 1) % remaining.len()],
+                remaining[idx],
+                remaining[(idx + 1) % remaining.len()],
+            ]
+        };
+        let turn = |[prev, current, next]: [usize; 3]| {
+            cross_2d(points[prev], points[current], points[next])
+        };
+
+        let is_ear = |idx: usize| {
+            let [prev, current, next] = corner(idx);
+            if turn([prev, current, next]) <= epsilon {
+                // Reflex or collinear corner
+                return false;
+            }
+
+            let triangle = [points[prev], points[current], points[next]];
+            !remaining
+                .iter()
+                .filter(|&&other| other != prev && other != current && other != next)
+                .any(|&other| is_inside_triangle(points[other], triangle))
+        };
+        // Cutting off a collinear corner leaves the rest of the polygon as it is. It is only done
+        // when no proper ear is left, so that such vertices still end up in real triangles where
+        // possible.
+        let is_collinear = |idx: usize| turn(corner(idx)).abs() <= epsilon;
+
+        let Some(idx) = (0..remaining.len())
+            .find(|&idx| is_ear(idx))
+            .or_else(|| (0..remaining.len()).find(|&idx| is_collinear(idx)))
+        else {
+            return Err(TriangulationError::NoEarFound);
+        };
+
+        triangles.push(corner(idx));
+        remaining.remove(idx);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+
+    Ok(triangles)
+}
@@ -0,0 +1,408 @@
+use crate::buffers::depth::DepthBuffer;
+use crate::buffers::frame::FrameBuffer;
+use crate::buffers::pixel_format::PixelFormat;
+use crate::common::camera::Camera;
+use crate::common::frustum::{BoundingSphere, Frustum};
+use crate::common::primitives::PolygonPoints2;
+use crate::common::space::{
+    to_homogeneous_clip_point, to_normal_transform, to_world_box, ModelToWorldTransform,
+    ModelVector, WorldBox, WorldPoint, WorldSize, WorldToClipTransform,
+};
+use crate::common::traits::{Bounded, Dimensionable, Positionable};
+use crate::lights::shadow_map::{ShadowCaster, ShadowMap};
+use crate::lights::traits::LightSource;
+use crate::objects::mesh::{Mesh, MeshVertex};
+use crate::objects::object::Object;
+use crate::shaders::geometry::GeometryShader;
+use crate::shaders::shading::{GouraudShading, Material, PhongShading, ShadingModel};
+use crate::shaders::traits::{Interpolate, SceneUniforms, Shader};
+use crate::shaders::vertex::{ClipVertex, VertexShader};
+use crate::shaders::vertex_post_processing::{
+    ClippingShader, CullingShader, ScreenVertex, Viewport,
+};
+use palette::Srgb;
+use std::ops::DerefMut;
+
+pub struct Renderer {
+    vertex_shader: VertexShader,
+    geometry_shader: GeometryShader,
+    culling_shader: CullingShader,
+    clipping_shader: ClippingShader,
+    frustum_culling: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self {
+            vertex_shader: VertexShader,
+            geometry_shader: GeometryShader,
+            culling_shader: CullingShader::default(),
+            clipping_shader: ClippingShader,
+            frustum_culling: true,
+        }
+    }
+
+    pub fn culling_shader(&self) -> &CullingShader {
+        &self.culling_shader
+    }
+
+    pub fn culling_shader_mut(&mut self) -> &mut CullingShader {
+        &mut self.culling_shader
+    }
+
+    pub fn frustum_culling(&self) -> bool {
+        self.frustum_culling
+    }
+
+    // Meshes whose bounds are entirely outside the view are skipped before their vertices are
+    // transformed. Only worth turning off to compare against.
+    pub fn set_frustum_culling(&mut self, frustum_culling: bool) {
+        self.frustum_culling = frustum_culling;
+    }
+
+    // Clears both buffers, everything drawn through the returned pass is seen from `camera` and
+    // mapped onto the whole frame buffer
+    pub fn begin_pass<'a, 'b, D, F>(
+        &'a self,
+        frame_buffer: &'a mut FrameBuffer<'b, D, F>,
+        depth_buffer: &'a mut DepthBuffer,
+        camera: &dyn Camera,
+        lights: &'a [&'a dyn LightSource],
+    ) -> RenderPass<'a, 'b, D, F>
+    where
+        D: DerefMut<Target = [F::Storage]>,
+        F: PixelFormat,
+    {
+        debug_assert_eq!(
+            frame_buffer.dimensions(),
+            depth_buffer.dimensions(),
+            "the depth buffer has to match the frame buffer"
+        );
+        frame_buffer.clear();
+        depth_buffer.clear();
+
+        RenderPass {
+            renderer: self,
+            viewport: Viewport::new(frame_buffer.dimensions()),
+            frame_buffer,
+            depth_buffer,
+            world_to_clip: camera.view_projection_transform(),
+            frustum: self.frustum_culling.then(|| camera.frustum()),
+            culled_meshes: 0,
+            camera_position: camera.position(),
+            lights,
+        }
+    }
+
+    // Clears the shadow map and points it at everything inside the casters' bounds, as seen from
+    // `light`
+    pub fn begin_shadow_pass<'a>(
+        &'a self,
+        shadow_map: &'a mut ShadowMap,
+        light: &dyn ShadowCaster,
+        casters: &[&dyn Bounded],
+    ) -> ShadowPass<'a> {
+        let scene_bounds = casters
+            .iter()
+            .map(|caster| caster.calculate_bounding_box())
+            .reduce(|union, bounds| {
+                WorldBox::new(union.min.min(bounds.min), union.max.max(bounds.max))
+            })
+            .unwrap_or_default();
+
+        shadow_map.set_world_to_clip(light.light_to_clip(scene_bounds));
+        self.shadow_pass(shadow_map)
+    }
+
+    // Same as `begin_shadow_pass`, with the projection left to `camera`, e.g. an
+    // `OrthographicCamera` framing just part of a large scene
+    pub fn begin_shadow_pass_from_camera<'a>(
+        &'a self,
+        shadow_map: &'a mut ShadowMap,
+        camera: &dyn Camera,
+    ) -> ShadowPass<'a> {
+        shadow_map.set_world_to_clip(camera.view_projection_transform());
+        self.shadow_pass(shadow_map)
+    }
+
+    fn shadow_pass<'a>(&'a self, shadow_map: &'a mut ShadowMap) -> ShadowPass<'a> {
+        shadow_map.depth_buffer_mut().clear();
+
+        ShadowPass {
+            frustum: self
+                .frustum_culling
+                .then(|| Frustum::from_world_to_clip(&shadow_map.world_to_clip())),
+            culled_meshes: 0,
+            clip_vertices: Vec::new(),
+            culling_shader: CullingShader::new(
+                shadow_map.face_culling,
+                self.culling_shader.front_face_winding(),
+            ),
+            renderer: self,
+            shadow_map,
+        }
+    }
+
+    // Culls, clips and maps the assembled triangles to the viewport, then hands every visible
+    // one over for rasterization
+    fn process_triangles<V: Interpolate>(
+        &self,
+        culling_shader: &CullingShader,
+        viewport: &Viewport,
+        clip_vertices: &[ClipVertex<V>],
+        face_indices: &[[usize; 3]],
+        mut rasterize: impl FnMut(&[ScreenVertex<V>; 3], &PolygonPoints2),
+    ) {
+        let triangles = self.geometry_shader.assemble(clip_vertices, face_indices);
+        for triangle in triangles {
+            if culling_shader.cull(&triangle) {
+                continue;
+            }
+
+            for clipped_triangle in self.clipping_shader.clip_triangle(triangle).triangles() {
+                let screen_vertices = clipped_triangle.map(|vertex| viewport.to_screen(&vertex));
+                let polygon = PolygonPoints2::new(screen_vertices.map(|vertex| vertex.position));
+                if culling_shader.cull_screen(&polygon) {
+                    continue;
+                }
+
+                rasterize(&screen_vertices, &polygon);
+            }
+        }
+    }
+}
+
+pub struct RenderPass<'a, 'b, D, F>
+where
+    D: DerefMut<Target = [F::Storage]>,
+    F: PixelFormat,
+{
+    renderer: &'a Renderer,
+    viewport: Viewport,
+    frame_buffer: &'a mut FrameBuffer<'b, D, F>,
+    depth_buffer: &'a mut DepthBuffer,
+    world_to_clip: WorldToClipTransform,
+    frustum: Option<Frustum>,
+    culled_meshes: usize,
+    camera_position: WorldPoint,
+    lights: &'a [&'a dyn LightSource],
+}
+
+impl<D, F> RenderPass<'_, '_, D, F>
+where
+    D: DerefMut<Target = [F::Storage]>,
+    F: PixelFormat,
+{
+    pub fn draw<S>(
+        &mut self,
+        mesh: &Mesh,
+        model_to_world: &ModelToWorldTransform,
+        shader: &S,
+        uniforms: &S::Uniforms,
+    ) where
+        S: Shader<Attributes = MeshVertex>,
+    {
+        if !is_visible(self.frustum.as_ref(), mesh, model_to_world) {
+            self.culled_meshes += 1;
+            return;
+        }
+
+        let renderer = self.renderer;
+        let scene = SceneUniforms {
+            model_to_world: *model_to_world,
+            normal_to_world: to_normal_transform(model_to_world),
+            model_to_clip: model_to_world.then(self.world_to_clip),
+            camera_position: self.camera_position,
+            lights: self.lights,
+        };
+
+        // Typed by the shader's varyings, which may change from one draw to the next
+        let mut clip_vertices = Vec::with_capacity(mesh.vertices.len());
+        renderer.vertex_shader.process(
+            shader,
+            uniforms,
+            &scene,
+            &mesh.vertices,
+            &mut clip_vertices,
+        );
+
+        renderer.process_triangles(
+            &renderer.culling_shader,
+            &self.viewport,
+            &clip_vertices,
+            mesh.tris_face_indices(),
+            |screen_vertices, polygon| {
+                self.frame_buffer.shade_triangle(
+                    self.depth_buffer,
+                    polygon,
+                    screen_vertices.map(|vertex| vertex.depth),
+                    |weights| {
+                        let varyings = ScreenVertex::interpolate_varyings(screen_vertices, weights);
+                        shader
+                            .fragment(&varyings, uniforms, &scene)
+                            .map(Srgb::<u8>::from_linear)
+                    },
+                );
+            },
+        );
+    }
+
+    // How many meshes the frustum test has skipped so far
+    pub fn culled_meshes(&self) -> usize {
+        self.culled_meshes
+    }
+
+    // Draws every node of the hierarchy that has a mesh, with its world transform
+    pub fn draw_object<S>(&mut self, object: &Object, shader: &S, uniforms: &S::Uniforms)
+    where
+        S: Shader<Attributes = MeshVertex>,
+    {
+        debug_assert!(
+            !object.needs_world_transform_update(),
+            "update_world_transforms has to be called after moving an object"
+        );
+        object.visit(&mut |node| {
+            if let Some(mesh) = node.mesh() {
+                self.draw(mesh, &node.world_transform(), shader, uniforms);
+            }
+        });
+    }
+
+    // Same as `draw_object`, with the built-in shader for `shading_model`
+    pub fn draw_shaded(
+        &mut self,
+        object: &Object,
+        shading_model: ShadingModel,
+        material: &Material,
+    ) {
+        match shading_model {
+            ShadingModel::Gouraud(specular_model) => {
+                self.draw_object(object, &GouraudShading { specular_model }, material)
+            }
+            ShadingModel::Phong(specular_model) => {
+                self.draw_object(object, &PhongShading { specular_model }, material)
+            }
+        }
+    }
+}
+
+// Renders depth only, from a light into its shadow map
+pub struct ShadowPass<'a> {
+    renderer: &'a Renderer,
+    shadow_map: &'a mut ShadowMap,
+    culling_shader: CullingShader,
+    // Casters outside the light's view would be clipped away entirely anyway
+    frustum: Option<Frustum>,
+    culled_meshes: usize,
+    // Reused from draw to draw
+    clip_vertices: Vec<ClipVertex<()>>,
+}
+
+impl ShadowPass<'_> {
+    pub fn draw(&mut self, mesh: &Mesh, model_to_world: &ModelToWorldTransform) {
+        if !is_visible(self.frustum.as_ref(), mesh, model_to_world) {
+            self.culled_meshes += 1;
+            return;
+        }
+
+        let model_to_clip = model_to_world.then(self.shadow_map.world_to_clip());
+        self.clip_vertices.clear();
+        self.clip_vertices
+            .extend(mesh.vertices.iter().map(|vertex| ClipVertex {
+                position: to_homogeneous_clip_point(&model_to_clip, vertex.position),
+                varyings: (),
+            }));
+
+        let shadow_map = &mut *self.shadow_map;
+        let viewport = Viewport::new(shadow_map.dimensions());
+        self.renderer.process_triangles(
+            &self.culling_shader,
+            &viewport,
+            &self.clip_vertices,
+            mesh.tris_face_indices(),
+            |screen_vertices, polygon| {
+                let depths = shadow_map.offset_depths(
+                    &screen_vertices.map(|vertex| vertex.position),
+                    screen_vertices.map(|vertex| vertex.depth),
+                );
+                shadow_map.depth_buffer_mut().fill_triangle(polygon, depths);
+            },
+        );
+    }
+
+    pub fn culled_meshes(&self) -> usize {
+        self.culled_meshes
+    }
+
+    pub fn draw_object(&mut self, object: &Object) {
+        debug_assert!(
+            !object.needs_world_transform_update(),
+            "update_world_transforms has to be called after moving an object"
+        );
+        object.visit(&mut |node| {
+            if let Some(mesh) = node.mesh() {
+                self.draw(mesh, &node.world_transform());
+            }
+        });
+    }
+}
+
+fn is_visible(
+    frustum: Option<&Frustum>,
+    mesh: &Mesh,
+    model_to_world: &ModelToWorldTransform,
+) -> bool {
+    frustum.map_or(true, |frustum| {
+        frustum.intersects(&MeshBounds {
+            mesh,
+            model_to_world,
+        })
+    })
+}
+
+// A mesh placed in the world, for `Frustum::intersects` to cull it by its sphere before its box
+struct MeshBounds<'a> {
+    mesh: &'a Mesh,
+    model_to_world: &'a ModelToWorldTransform,
+}
+
+impl Positionable for MeshBounds<'_> {
+    fn get_position(&self) -> WorldPoint {
+        self.calculate_bounding_box().min
+    }
+}
+
+impl Dimensionable for MeshBounds<'_> {
+    fn get_dimensions(&self) -> WorldSize {
+        let bounding_box = self.calculate_bounding_box();
+        WorldSize::from_vector(bounding_box.max - bounding_box.min)
+    }
+}
+
+impl Bounded for MeshBounds<'_> {
+    fn calculate_bounding_box(&self) -> WorldBox {
+        to_world_box(self.model_to_world, &self.mesh.bounding_box())
+    }
+
+    // Built from the mesh's own box, which stays tight when the box in world space grows by
+    // being rotated
+    fn calculate_bounding_sphere(&self) -> BoundingSphere {
+        let model_box = self.mesh.bounding_box();
+        let half_diagonal = (model_box.max - model_box.min) / 2.0;
+        let largest_scale = [ModelVector::X, ModelVector::Y, ModelVector::Z]
+            .map(|axis| self.model_to_world.map_vector(axis).length())
+            .into_iter()
+            .fold(0.0, f32::max);
+
+        BoundingSphere {
+            center: self.model_to_world.map_point(model_box.min + half_diagonal),
+            radius: half_diagonal.length() * largest_scale,
+        }
+    }
+}
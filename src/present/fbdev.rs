@@ -0,0 +1,543 @@
+use crate::buffers::frame::FrameBuffer;
+use crate::buffers::image::Image;
+use crate::buffers::pixel_format::{PixelFormat, SurfaceFormat, Xrgb8888};
+use crate::present::traits::*;
+use derive_more::{Display, From};
+use glamour::Vector2;
+use memmap2::{MmapMut, MmapOptions};
+use palette::Srgb;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::fd::{AsRawFd, RawFd};
+use std::path::Path;
+
+#[derive(Debug, Display, From)]
+pub enum FbdevError {
+    #[display("framebuffer device error: {_0}")]
+    Io(io::Error),
+    #[display("unsupported framebuffer pixel depth of {_0} bits")]
+    #[from(ignore)]
+    UnsupportedPixelDepth(u32),
+    #[display("framebuffer memory holds {actual} bytes, but its layout needs {expected}")]
+    #[from(ignore)]
+    TooSmall { expected: usize, actual: usize },
+    #[display(
+        "framebuffer lines are {line_length} bytes, too short for {expected} bytes of pixels"
+    )]
+    #[from(ignore)]
+    LineTooShort { line_length: u32, expected: usize },
+    #[display("framebuffer is {virtual_height} lines high, too short for {height} visible lines")]
+    #[from(ignore)]
+    VirtualHeightTooSmall { virtual_height: u32, height: u32 },
+    #[display("cannot present an empty {width}x{height} image")]
+    #[from(ignore)]
+    EmptyImage { width: u32, height: u32 },
+}
+
+// ioctl requests from linux/fb.h
+const FBIOGET_VSCREENINFO: u32 = 0x4600;
+const FBIOPUT_VSCREENINFO: u32 = 0x4601;
+const FBIOGET_FSCREENINFO: u32 = 0x4602;
+const FBIOPAN_DISPLAY: u32 = 0x4606;
+
+// Mirror the kernel's structs field for field, not every field is read
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+#[allow(dead_code)]
+struct Bitfield {
+    offset: u32,
+    length: u32,
+    msb_right: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+#[allow(dead_code)]
+struct VarScreenInfo {
+    xres: u32,
+    yres: u32,
+    xres_virtual: u32,
+    yres_virtual: u32,
+    xoffset: u32,
+    yoffset: u32,
+    bits_per_pixel: u32,
+    grayscale: u32,
+    red: Bitfield,
+    green: Bitfield,
+    blue: Bitfield,
+    transp: Bitfield,
+    nonstd: u32,
+    activate: u32,
+    height: u32,
+    width: u32,
+    accel_flags: u32,
+    pixclock: u32,
+    left_margin: u32,
+    right_margin: u32,
+    upper_margin: u32,
+    lower_margin: u32,
+    hsync_len: u32,
+    vsync_len: u32,
+    sync: u32,
+    vmode: u32,
+    rotate: u32,
+    colorspace: u32,
+    reserved: [u32; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+#[allow(dead_code)]
+struct FixScreenInfo {
+    id: [u8; 16],
+    smem_start: libc::c_ulong,
+    smem_len: u32,
+    fb_type: u32,
+    type_aux: u32,
+    visual: u32,
+    xpanstep: u16,
+    ypanstep: u16,
+    ywrapstep: u16,
+    line_length: u32,
+    mmio_start: libc::c_ulong,
+    mmio_len: u32,
+    accel: u32,
+    capabilities: u16,
+    reserved: [u16; 2],
+}
+
+fn ioctl<T>(fd: RawFd, request: u32, argument: &mut T) -> io::Result<()> {
+    // SAFETY: callers pass the struct the kernel reads or writes for `request`
+    if unsafe { libc::ioctl(fd, request as _, argument as *mut T) } < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+// Where a color channel sits inside a device pixel
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChannelLayout {
+    pub offset: u32,
+    pub length: u32,
+}
+
+impl ChannelLayout {
+    pub fn new(offset: u32, length: u32) -> Self {
+        Self { offset, length }
+    }
+
+    fn encode(&self, value: u8) -> u32 {
+        if self.length == 0 {
+            return 0;
+        }
+
+        let value = if self.length <= 8 {
+            value as u32 >> (8 - self.length)
+        } else {
+            (value as u32) << (self.length - 8)
+        };
+        value << self.offset
+    }
+}
+
+impl From<Bitfield> for ChannelLayout {
+    fn from(bitfield: Bitfield) -> Self {
+        Self::new(bitfield.offset, bitfield.length)
+    }
+}
+
+// Geometry and pixel format of a framebuffer's memory
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FramebufferLayout {
+    // Visible part, one page
+    pub dimensions: Vector2<u32>,
+    // Rows in memory, at least two pages' worth allow flipping
+    pub virtual_height: u32,
+    pub bits_per_pixel: u32,
+    // Bytes from one row to the next, may include padding
+    pub line_length: u32,
+    pub red: ChannelLayout,
+    pub green: ChannelLayout,
+    pub blue: ChannelLayout,
+    // Set to opaque when the device has one
+    pub alpha: ChannelLayout,
+}
+
+impl FramebufferLayout {
+    pub fn xrgb8888(dimensions: Vector2<u32>) -> Self {
+        Self {
+            dimensions,
+            virtual_height: dimensions.y,
+            bits_per_pixel: 32,
+            line_length: dimensions.x * 4,
+            red: ChannelLayout::new(16, 8),
+            green: ChannelLayout::new(8, 8),
+            blue: ChannelLayout::new(0, 8),
+            alpha: ChannelLayout::default(),
+        }
+    }
+
+    // What the Funkey S's display uses
+    pub fn rgb565(dimensions: Vector2<u32>) -> Self {
+        Self {
+            dimensions,
+            virtual_height: dimensions.y,
+            bits_per_pixel: 16,
+            line_length: dimensions.x * 2,
+            red: ChannelLayout::new(11, 5),
+            green: ChannelLayout::new(5, 6),
+            blue: ChannelLayout::new(0, 5),
+            alpha: ChannelLayout::default(),
+        }
+    }
+
+    fn from_screen_info(var_info: &VarScreenInfo, fix_info: &FixScreenInfo) -> Self {
+        Self {
+            dimensions: Vector2::new(var_info.xres, var_info.yres),
+            virtual_height: var_info.yres_virtual,
+            bits_per_pixel: var_info.bits_per_pixel,
+            line_length: fix_info.line_length,
+            red: var_info.red.into(),
+            green: var_info.green.into(),
+            blue: var_info.blue.into(),
+            alpha: var_info.transp.into(),
+        }
+    }
+
+    pub fn bytes_per_pixel(&self) -> usize {
+        self.bits_per_pixel as usize / 8
+    }
+
+    // Bytes of memory the layout spans
+    pub fn size(&self) -> usize {
+        self.line_length as usize * self.virtual_height as usize
+    }
+
+    pub fn page_count(&self) -> u32 {
+        self.virtual_height / self.dimensions.y.max(1)
+    }
+
+    // Converts a color into the device's format
+    pub fn encode(&self, color: Srgb<u8>) -> u32 {
+        self.red.encode(color.red)
+            | self.green.encode(color.green)
+            | self.blue.encode(color.blue)
+            | self.alpha.encode(u8::MAX)
+    }
+
+    fn validate(&self, available: usize) -> Result<(), FbdevError> {
+        if !matches!(self.bits_per_pixel, 16 | 24 | 32) {
+            return Err(FbdevError::UnsupportedPixelDepth(self.bits_per_pixel));
+        }
+
+        let row_length = self.dimensions.x as usize * self.bytes_per_pixel();
+        if (self.line_length as usize) < row_length {
+            return Err(FbdevError::LineTooShort {
+                line_length: self.line_length,
+                expected: row_length,
+            });
+        }
+        if self.virtual_height < self.dimensions.y {
+            return Err(FbdevError::VirtualHeightTooSmall {
+                virtual_height: self.virtual_height,
+                height: self.dimensions.y,
+            });
+        }
+
+        // With the checks above, this covers every visible pixel
+        let expected = self.size();
+        if available < expected {
+            return Err(FbdevError::TooSmall {
+                expected,
+                actual: available,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// Restores the console's screen info when closed
+struct DeviceFile {
+    file: File,
+    // Absent for plain files standing in for a device
+    original_var_info: Option<VarScreenInfo>,
+}
+
+impl Drop for DeviceFile {
+    fn drop(&mut self) {
+        if let Some(mut original_var_info) = self.original_var_info {
+            let _ = ioctl(
+                self.file.as_raw_fd(),
+                FBIOPUT_VSCREENINFO,
+                &mut original_var_info,
+            );
+        }
+    }
+}
+
+// A Linux framebuffer device (/dev/fb*) mapped into memory, so frames can be shown without a
+// display server. Frames are rendered as `F`, a format matching the device's is copied as is.
+pub struct FramebufferDevice<F: PixelFormat = Xrgb8888> {
+    device_file: DeviceFile,
+    memory: MmapMut,
+    layout: FramebufferLayout,
+    var_info: Option<VarScreenInfo>,
+    double_buffered: bool,
+    front_page: u32,
+    // Rendered into by `present_frame`, then converted into the device's format
+    frame: Vec<F::Storage>,
+}
+
+impl FramebufferDevice {
+    // Opens a device and queries its layout. A second page is requested for flipping, drivers
+    // that cannot provide one are used single-buffered.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, FbdevError> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let fd = file.as_raw_fd();
+
+        let mut original_var_info = VarScreenInfo::default();
+        ioctl(fd, FBIOGET_VSCREENINFO, &mut original_var_info)?;
+        let device_file = DeviceFile {
+            file,
+            original_var_info: Some(original_var_info),
+        };
+
+        let mut var_info = original_var_info;
+        if var_info.yres_virtual < var_info.yres * 2 {
+            let mut requested = var_info;
+            requested.yres_virtual = var_info.yres * 2;
+            requested.yoffset = 0;
+            if ioctl(fd, FBIOPUT_VSCREENINFO, &mut requested).is_ok() {
+                ioctl(fd, FBIOGET_VSCREENINFO, &mut var_info)?;
+            }
+        }
+
+        let mut fix_info = FixScreenInfo::default();
+        ioctl(fd, FBIOGET_FSCREENINFO, &mut fix_info)?;
+
+        let layout = FramebufferLayout::from_screen_info(&var_info, &fix_info);
+        let double_buffered = layout.page_count() >= 2 && fix_info.ypanstep > 0;
+        let front_page = (var_info.yoffset / var_info.yres.max(1)).min(1);
+
+        let mut device = Self::map(device_file, layout, fix_info.smem_len as usize)?;
+        device.var_info = Some(var_info);
+        device.double_buffered = double_buffered;
+        device.front_page = front_page;
+
+        Ok(device)
+    }
+
+    // Maps any file, e.g. a plain one standing in for a device, with the given layout. There is
+    // no page flipping without a device.
+    pub fn open_with_layout<P: AsRef<Path>>(
+        path: P,
+        layout: FramebufferLayout,
+    ) -> Result<Self, FbdevError> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let length = file.metadata()?.len() as usize;
+        let device_file = DeviceFile {
+            file,
+            original_var_info: None,
+        };
+
+        Self::map(device_file, layout, length)
+    }
+
+    fn map(
+        device_file: DeviceFile,
+        layout: FramebufferLayout,
+        available: usize,
+    ) -> Result<Self, FbdevError> {
+        layout.validate(available)?;
+
+        // SAFETY: devices cannot be truncated, and the mapping only lives as long as we hold
+        // the file open
+        let mut memory = unsafe {
+            MmapOptions::new()
+                .len(layout.size())
+                .map_mut(&device_file.file)?
+        };
+        memory.fill(0);
+
+        Ok(Self {
+            device_file,
+            memory,
+            layout,
+            var_info: None,
+            double_buffered: false,
+            front_page: 0,
+            frame: vec![0; layout.dimensions.x as usize * layout.dimensions.y as usize],
+        })
+    }
+}
+
+impl<F: PixelFormat> FramebufferDevice<F> {
+    // Renders frames as `G` from now on, pick the device's own format to skip converting them
+    pub fn with_format<G: PixelFormat>(self) -> FramebufferDevice<G> {
+        let black = G::pack(Srgb::new(0, 0, 0));
+        FramebufferDevice {
+            device_file: self.device_file,
+            memory: self.memory,
+            layout: self.layout,
+            var_info: self.var_info,
+            double_buffered: self.double_buffered,
+            front_page: self.front_page,
+            frame: vec![black; self.frame.len()],
+        }
+    }
+
+    pub fn layout(&self) -> &FramebufferLayout {
+        &self.layout
+    }
+
+    pub fn dimensions(&self) -> Vector2<u32> {
+        self.layout.dimensions
+    }
+
+    pub fn is_double_buffered(&self) -> bool {
+        self.double_buffered
+    }
+
+    // Memory of the page currently on screen
+    pub fn front_page(&self) -> &[u8] {
+        let page_size = self.layout.line_length as usize * self.layout.dimensions.y as usize;
+        let start = self.front_page as usize * page_size;
+        &self.memory[start..start + page_size]
+    }
+
+    // Converts `image` into the device's format at the top-left corner and shows it, the rest of
+    // the screen is cleared to black. With two pages it is drawn into the hidden one and panned
+    // to, otherwise written straight to the screen. Should panning fail, the device falls back to
+    // the latter.
+    pub fn present(&mut self, image: &Image) -> Result<(), FbdevError> {
+        self.present_pixels::<Xrgb8888>(image.data(), image.dimensions())
+    }
+
+    fn present_pixels<G: PixelFormat>(
+        &mut self,
+        pixels: &[G::Storage],
+        dimensions: Vector2<u32>,
+    ) -> Result<(), FbdevError> {
+        if dimensions.x == 0 || dimensions.y == 0 {
+            return Err(FbdevError::EmptyImage {
+                width: dimensions.x,
+                height: dimensions.y,
+            });
+        }
+
+        let width = dimensions.x;
+        if self.double_buffered {
+            let back_page = 1 - self.front_page;
+            self.write_page::<G>(back_page, pixels, width);
+
+            let mut var_info = self.var_info.unwrap_or_default();
+            var_info.xoffset = 0;
+            var_info.yoffset = back_page * self.layout.dimensions.y;
+            let fd = self.device_file.file.as_raw_fd();
+            if ioctl(fd, FBIOPAN_DISPLAY, &mut var_info).is_ok() {
+                self.front_page = back_page;
+            } else {
+                self.double_buffered = false;
+                self.write_page::<G>(self.front_page, pixels, width);
+            }
+        } else {
+            self.write_page::<G>(self.front_page, pixels, width);
+        }
+
+        // Only matters for plain files, device memory is the screen itself
+        self.memory.flush_async()?;
+
+        Ok(())
+    }
+
+    // `source_width` must not be zero
+    fn write_page<G: PixelFormat>(&mut self, page: u32, pixels: &[G::Storage], source_width: u32) {
+        let layout = self.layout;
+        let same_format = G::SURFACE_FORMAT == self.surface_format();
+        let bytes_per_pixel = layout.bytes_per_pixel();
+        let row_length = layout.dimensions.x as usize * bytes_per_pixel;
+        let height = layout.dimensions.y as usize;
+        let page_start = page as usize * height * layout.line_length as usize;
+        let black = layout.encode(Srgb::new(0, 0, 0)).to_le_bytes();
+
+        let mut source_rows = pixels.chunks_exact(source_width as usize);
+        for y in 0..height {
+            let row_start = page_start + y * layout.line_length as usize;
+            let row = &mut self.memory[row_start..row_start + row_length];
+            let source_row = source_rows.next().unwrap_or_default();
+            let (covered, uncovered) =
+                row.split_at_mut(row_length.min(source_row.len() * bytes_per_pixel));
+
+            // fbdev pixels are in the CPU's byte order, all our targets are little-endian
+            for (pixel, &color) in covered.chunks_exact_mut(bytes_per_pixel).zip(source_row) {
+                let encoded = if same_format {
+                    color.into()
+                } else {
+                    layout.encode(G::unpack(color))
+                };
+                pixel.copy_from_slice(&encoded.to_le_bytes()[..bytes_per_pixel]);
+            }
+            // Left alone, whatever a smaller image does not cover would keep showing older frames
+            for pixel in uncovered.chunks_exact_mut(bytes_per_pixel) {
+                pixel.copy_from_slice(&black[..bytes_per_pixel]);
+            }
+        }
+    }
+}
+
+impl<F: PixelFormat> PresentTarget for FramebufferDevice<F> {
+    type Format = F;
+
+    fn dimensions(&self) -> Vector2<u32> {
+        self.layout.dimensions
+    }
+
+    fn surface_format(&self) -> SurfaceFormat {
+        let layout = &self.layout;
+        let channels = (layout.red, layout.green, layout.blue);
+        let rgb888 = (
+            ChannelLayout::new(16, 8),
+            ChannelLayout::new(8, 8),
+            ChannelLayout::new(0, 8),
+        );
+        let rgb565 = (
+            ChannelLayout::new(11, 5),
+            ChannelLayout::new(5, 6),
+            ChannelLayout::new(0, 5),
+        );
+
+        match layout.bits_per_pixel {
+            32 if channels == rgb888 && layout.alpha.length == 0 => SurfaceFormat::Xrgb8888,
+            24 if channels == rgb888 => SurfaceFormat::Rgb888,
+            16 if channels == rgb565 => SurfaceFormat::Rgb565,
+            bits_per_pixel => SurfaceFormat::Other { bits_per_pixel },
+        }
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        if self.double_buffered {
+            PresentMode::Flip
+        } else {
+            PresentMode::Immediate
+        }
+    }
+
+    fn present_frame(
+        &mut self,
+        draw: &mut dyn FnMut(&mut TargetFrameBuffer<'_, '_, F>),
+    ) -> Result<(), PresentError> {
+        let dimensions = self.layout.dimensions;
+        let mut frame = std::mem::take(&mut self.frame);
+        {
+            let mut data: &mut [F::Storage] = &mut frame;
+            draw(&mut FrameBuffer::new(&mut data, dimensions));
+        }
+
+        let result = self.present_pixels::<F>(&frame, dimensions);
+        self.frame = frame;
+
+        Ok(result?)
+    }
+}
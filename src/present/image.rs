@@ -0,0 +1,32 @@
+use crate::buffers::frame::FrameBuffer;
+use crate::buffers::image::Image;
+use crate::buffers::pixel_format::{SurfaceFormat, Xrgb8888};
+use crate::present::traits::*;
+use glamour::Vector2;
+
+impl PresentTarget for Image {
+    type Format = Xrgb8888;
+
+    fn dimensions(&self) -> Vector2<u32> {
+        Image::dimensions(self)
+    }
+
+    fn surface_format(&self) -> SurfaceFormat {
+        SurfaceFormat::Xrgb8888
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        PresentMode::Offscreen
+    }
+
+    fn present_frame(
+        &mut self,
+        draw: &mut dyn FnMut(&mut TargetFrameBuffer<'_, '_, Xrgb8888>),
+    ) -> Result<(), PresentError> {
+        let dimensions = Image::dimensions(self);
+        let mut data = self.data_mut();
+        draw(&mut FrameBuffer::new(&mut data, dimensions));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+#[cfg(target_os = "linux")]
+pub mod fbdev;
+pub mod image;
+pub mod traits;
+pub mod window;
@@ -0,0 +1,51 @@
+use crate::buffers::frame::FrameBuffer;
+use crate::buffers::pixel_format::{PixelFormat, SurfaceFormat};
+#[cfg(target_os = "linux")]
+use crate::present::fbdev::FbdevError;
+use derive_more::{Display, From};
+use glamour::Vector2;
+
+#[derive(Debug, Display, From)]
+pub enum PresentError {
+    #[display("failed to present to the window: {_0}")]
+    Window(softbuffer::SoftBufferError),
+    #[cfg(target_os = "linux")]
+    #[display("failed to present to the framebuffer: {_0}")]
+    Fbdev(FbdevError),
+}
+
+// How a finished frame reaches the screen
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresentMode {
+    // Kept in memory, nothing is shown
+    Offscreen,
+    // Copied to the screen by the window system
+    Blit,
+    // Drawn into a hidden page that is then flipped to, without tearing
+    Flip,
+    // Drawn straight into the visible memory, may tear
+    Immediate,
+}
+
+// A frame buffer over a borrowed slice, as handed out by presentation targets
+pub type TargetFrameBuffer<'a, 'b, F> = FrameBuffer<'a, &'b mut [<F as PixelFormat>::Storage], F>;
+
+// Anything frames can be rendered into and shown on, so the same application runs in a desktop
+// window, on a bare Linux framebuffer or without any display
+pub trait PresentTarget {
+    // What frames are rendered as, converted into `surface_format` on presentation if the two
+    // differ
+    type Format: PixelFormat;
+
+    fn dimensions(&self) -> Vector2<u32>;
+
+    fn surface_format(&self) -> SurfaceFormat;
+
+    fn present_mode(&self) -> PresentMode;
+
+    // Lets `draw` render into a frame buffer of the target's dimensions, then shows the result
+    fn present_frame(
+        &mut self,
+        draw: &mut dyn FnMut(&mut TargetFrameBuffer<'_, '_, Self::Format>),
+    ) -> Result<(), PresentError>;
+}
@@ -0,0 +1,164 @@
+use crate::buffers::frame::FrameBuffer;
+use crate::buffers::pixel_format::{SurfaceFormat, Xrgb8888};
+use crate::present::traits::*;
+use glamour::{Point2, Rect, Size2, Vector2};
+use softbuffer::{Context, Surface};
+use std::num::NonZeroU32;
+use std::rc::Rc;
+use winit::window::Window;
+
+// A winit window drawn into through softbuffer
+pub struct WindowTarget {
+    window: Rc<Window>,
+    surface: Surface<Rc<Window>, Rc<Window>>,
+    // Frames are rendered at this size and scaled to fit the window if set, otherwise they
+    // follow the window's size
+    fixed_resolution: Option<Vector2<u32>>,
+    frame: Vec<u32>,
+}
+
+impl WindowTarget {
+    pub fn new(window: Rc<Window>) -> Result<Self, PresentError> {
+        let context = Context::new(window.clone())?;
+        let surface = Surface::new(&context, window.clone())?;
+
+        Ok(Self {
+            window,
+            surface,
+            fixed_resolution: None,
+            frame: Vec::new(),
+        })
+    }
+
+    // Renders at `resolution` whatever the window's size, scaled up or down keeping its aspect
+    // ratio, with black bars filling the rest
+    pub fn with_fixed_resolution(
+        window: Rc<Window>,
+        resolution: Vector2<u32>,
+    ) -> Result<Self, PresentError> {
+        let mut target = Self::new(window)?;
+        target.fixed_resolution = Some(resolution);
+        target.frame = vec![0; (resolution.x * resolution.y) as usize];
+
+        Ok(target)
+    }
+
+    pub fn window(&self) -> &Rc<Window> {
+        &self.window
+    }
+
+    pub fn fixed_resolution(&self) -> Option<Vector2<u32>> {
+        self.fixed_resolution
+    }
+
+    fn window_dimensions(&self) -> Vector2<u32> {
+        let size = self.window.inner_size();
+        Vector2::new(size.width, size.height)
+    }
+}
+
+impl PresentTarget for WindowTarget {
+    type Format = Xrgb8888;
+
+    fn dimensions(&self) -> Vector2<u32> {
+        self.fixed_resolution
+            .unwrap_or_else(|| self.window_dimensions())
+    }
+
+    fn surface_format(&self) -> SurfaceFormat {
+        SurfaceFormat::Xrgb8888
+    }
+
+    fn present_mode(&self) -> PresentMode {
+        PresentMode::Blit
+    }
+
+    fn present_frame(
+        &mut self,
+        draw: &mut dyn FnMut(&mut TargetFrameBuffer<'_, '_, Xrgb8888>),
+    ) -> Result<(), PresentError> {
+        // Minimized windows have nothing to show
+        let window_dimensions = self.window_dimensions();
+        let (Some(width), Some(height)) = (
+            NonZeroU32::new(window_dimensions.x),
+            NonZeroU32::new(window_dimensions.y),
+        ) else {
+            return Ok(());
+        };
+        self.surface.resize(width, height)?;
+
+        let mut buffer = self.surface.buffer_mut()?;
+        match self.fixed_resolution {
+            Some(resolution) => {
+                {
+                    let mut data: &mut [u32] = &mut self.frame;
+                    draw(&mut FrameBuffer::new(&mut data, resolution));
+                }
+                letterbox(&self.frame, resolution, &mut buffer, window_dimensions);
+            }
+            None => {
+                let mut data: &mut [u32] = &mut buffer;
+                draw(&mut FrameBuffer::new(&mut data, window_dimensions));
+            }
+        }
+        buffer.present()?;
+
+        Ok(())
+    }
+}
+
+// Where `letterbox` puts a `source_dimensions` frame inside a `target_dimensions` buffer: as large
+// as fits with the aspect ratio kept, rounded down, and centered with any odd pixel of the bars
+// going right or down. Empty frames or targets have nowhere to go.
+pub fn letterbox_rect(
+    source_dimensions: Vector2<u32>,
+    target_dimensions: Vector2<u32>,
+) -> Option<Rect<u32>> {
+    if source_dimensions.x == 0
+        || source_dimensions.y == 0
+        || target_dimensions.x == 0
+        || target_dimensions.y == 0
+    {
+        return None;
+    }
+
+    // Cross-multiplied in integers, as float scale factors can round a whole pixel away
+    let (source_x, source_y) = (source_dimensions.x as u64, source_dimensions.y as u64);
+    let (target_x, target_y) = (target_dimensions.x as u64, target_dimensions.y as u64);
+    let (scaled_width, scaled_height) = if target_x * source_y <= target_y * source_x {
+        (target_x, target_x * source_y / source_x)
+    } else {
+        (target_y * source_x / source_y, target_y)
+    };
+    let size = Size2::new(scaled_width.max(1) as u32, scaled_height.max(1) as u32);
+    let origin = Point2::new(
+        (target_dimensions.x - size.width) / 2,
+        (target_dimensions.y - size.height) / 2,
+    );
+
+    Some(Rect::new(origin, size))
+}
+
+// Scales `source` into `letterbox_rect` of `target` with nearest-neighbor sampling, clearing the
+// bars around it
+fn letterbox(
+    source: &[u32],
+    source_dimensions: Vector2<u32>,
+    target: &mut [u32],
+    target_dimensions: Vector2<u32>,
+) {
+    target.fill(0);
+    let Some(rect) = letterbox_rect(source_dimensions, target_dimensions) else {
+        return;
+    };
+
+    for y in 0..rect.size.height {
+        let source_y = y * source_dimensions.y / rect.size.height;
+        let source_row = &source[(source_y * source_dimensions.x) as usize..];
+        let target_start = ((rect.origin.y + y) * target_dimensions.x + rect.origin.x) as usize;
+        let target_row = &mut target[target_start..target_start + rect.size.width as usize];
+        for (x, pixel) in target_row.iter_mut().enumerate() {
+            *pixel = source_row[x * source_dimensions.x as usize / rect.size.width as usize];
+        }
+    }
+}
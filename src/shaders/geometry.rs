@@ -1,5 +1,16 @@
-use crate::shaders::traits::*;
+use crate::shaders::vertex::ClipVertex;
 
+// Primitive assembly, turns processed vertices and face indices into triangles
 pub struct GeometryShader;
 
-impl Shader for GeometryShader {}
+impl GeometryShader {
+    pub fn assemble<'a, V: Copy>(
+        &self,
+        vertices: &'a [ClipVertex<V>],
+        face_indices: &'a [[usize; 3]],
+    ) -> impl Iterator<Item = [ClipVertex<V>; 3]> + 'a {
+        face_indices
+            .iter()
+            .map(|&[a, b, c]| [vertices[a], vertices[b], vertices[c]])
+    }
+}
@@ -1,5 +1,5 @@
 pub mod geometry;
 pub mod shading;
-mod traits;
+pub mod traits;
 pub mod vertex;
 pub mod vertex_post_processing;
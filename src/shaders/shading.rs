@@ -1,8 +1,208 @@
+use crate::common::space::{to_homogeneous_clip_point, TexturePoint, WorldPoint, WorldVector};
+use crate::objects::mesh::MeshVertex;
 use crate::shaders::traits::*;
+use crate::shaders::vertex::ClipVertex;
+use crate::textures::sampler::Sampler;
+use crate::textures::texture::Texture;
+use palette::{LinSrgb, Srgba};
+use std::rc::Rc;
 
-pub struct GouraudShading;
-pub struct PhongShading;
+#[derive(Clone, Debug)]
+pub struct Material {
+    pub ambient: LinSrgb,
+    pub diffuse: LinSrgb,
+    pub specular: LinSrgb,
+    pub shininess: f32,
+    // Multiplies both the ambient and the diffuse color when present
+    pub diffuse_texture: Option<Rc<Texture<Srgba<u8>>>>,
+    pub sampler: Sampler,
+}
 
-impl Shader for GouraudShading {}
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            ambient: LinSrgb::new(0.05, 0.05, 0.05),
+            diffuse: LinSrgb::new(0.8, 0.8, 0.8),
+            specular: LinSrgb::new(0.2, 0.2, 0.2),
+            shininess: 32.0,
+            diffuse_texture: None,
+            sampler: Sampler::default(),
+        }
+    }
+}
 
-impl Shader for PhongShading {}
+impl Material {
+    fn base_color(&self, texture_coordinates: TexturePoint) -> LinSrgb {
+        match &self.diffuse_texture {
+            Some(texture) => texture.sample(&self.sampler, texture_coordinates).color,
+            None => LinSrgb::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpecularModel {
+    // Reflected light direction against the view direction
+    Phong,
+    // Half vector against the normal, cheaper and better behaved at grazing angles
+    #[default]
+    BlinnPhong,
+}
+
+// Light reaching the eye from all lights, split so that the diffuse part can be tinted by the
+// surface color afterwards
+#[derive(Clone, Copy)]
+struct Lighting {
+    diffuse: LinSrgb,
+    specular: LinSrgb,
+}
+
+impl Lighting {
+    fn gather(
+        material: &Material,
+        specular_model: SpecularModel,
+        position: WorldPoint,
+        normal: WorldVector,
+        scene: &SceneUniforms,
+    ) -> Self {
+        let view_direction = (scene.camera_position - position).normalize();
+        let mut lighting = Self {
+            diffuse: LinSrgb::new(0.0, 0.0, 0.0),
+            specular: LinSrgb::new(0.0, 0.0, 0.0),
+        };
+
+        for light in scene.lights {
+            let incident = light.incident_light(position);
+            let lambert = normal.dot(incident.direction);
+            if lambert <= 0.0 {
+                continue;
+            }
+
+            let specular_angle = match specular_model {
+                SpecularModel::Phong => {
+                    let reflected = normal * (2.0 * lambert) - incident.direction;
+                    reflected.dot(view_direction)
+                }
+                SpecularModel::BlinnPhong => {
+                    let halfway = (incident.direction + view_direction).normalize_or_zero();
+                    normal.dot(halfway)
+                }
+            };
+
+            lighting.diffuse += incident.radiance * lambert;
+            lighting.specular +=
+                incident.radiance * specular_angle.max(0.0).powf(material.shininess);
+        }
+
+        lighting
+    }
+
+    fn shade(&self, material: &Material, base_color: LinSrgb) -> LinSrgb {
+        base_color * (material.ambient + material.diffuse * self.diffuse)
+            + material.specular * self.specular
+    }
+}
+
+// Lights the vertices and interpolates the result, cheap but loses highlights inside triangles
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GouraudShading {
+    pub specular_model: SpecularModel,
+}
+
+impl Shader for GouraudShading {
+    type Attributes = MeshVertex;
+    type Uniforms = Material;
+    type Varyings = (LinSrgb, LinSrgb, TexturePoint);
+
+    fn vertex(
+        &self,
+        vertex: &MeshVertex,
+        material: &Material,
+        scene: &SceneUniforms,
+    ) -> ClipVertex<Self::Varyings> {
+        let lighting = Lighting::gather(
+            material,
+            self.specular_model,
+            scene.model_to_world.map_point(vertex.position),
+            scene
+                .normal_to_world
+                .map_vector(vertex.normal)
+                .normalize_or_zero(),
+            scene,
+        );
+
+        ClipVertex {
+            position: to_homogeneous_clip_point(&scene.model_to_clip, vertex.position),
+            varyings: (
+                lighting.diffuse,
+                lighting.specular,
+                vertex.texture_coordinates,
+            ),
+        }
+    }
+
+    fn fragment(
+        &self,
+        &(diffuse, specular, texture_coordinates): &Self::Varyings,
+        material: &Material,
+        _scene: &SceneUniforms,
+    ) -> Option<LinSrgb> {
+        let lighting = Lighting { diffuse, specular };
+        Some(lighting.shade(material, material.base_color(texture_coordinates)))
+    }
+}
+
+// Lights every fragment with the interpolated normal
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PhongShading {
+    pub specular_model: SpecularModel,
+}
+
+impl Shader for PhongShading {
+    type Attributes = MeshVertex;
+    type Uniforms = Material;
+    type Varyings = (WorldPoint, WorldVector, TexturePoint);
+
+    fn vertex(
+        &self,
+        vertex: &MeshVertex,
+        _material: &Material,
+        scene: &SceneUniforms,
+    ) -> ClipVertex<Self::Varyings> {
+        ClipVertex {
+            position: to_homogeneous_clip_point(&scene.model_to_clip, vertex.position),
+            varyings: (
+                scene.model_to_world.map_point(vertex.position),
+                scene
+                    .normal_to_world
+                    .map_vector(vertex.normal)
+                    .normalize_or_zero(),
+                vertex.texture_coordinates,
+            ),
+        }
+    }
+
+    fn fragment(
+        &self,
+        &(position, normal, texture_coordinates): &Self::Varyings,
+        material: &Material,
+        scene: &SceneUniforms,
+    ) -> Option<LinSrgb> {
+        let lighting = Lighting::gather(
+            material,
+            self.specular_model,
+            position,
+            normal.normalize_or_zero(),
+            scene,
+        );
+        Some(lighting.shade(material, material.base_color(texture_coordinates)))
+    }
+}
+
+// Lets the shading model be picked at runtime, e.g. falling back to Gouraud when frames take
+// too long. `RenderPass::draw_shaded` draws with the matching shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShadingModel {
+    Gouraud(SpecularModel),
+    Phong(SpecularModel),
+}
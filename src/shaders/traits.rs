@@ -1 +1,152 @@
-pub trait Shader {}
+use crate::common::space::{ModelToClipTransform, ModelToWorldTransform, WorldPoint};
+use crate::lights::traits::LightSource;
+use crate::shaders::vertex::ClipVertex;
+use glamour::{Point2, Point3, Point4, Unit, Vector2, Vector3, Vector4};
+use palette::{LinSrgb, LinSrgba};
+
+// Built-in uniforms the renderer fills in for every draw
+#[derive(Clone, Copy)]
+pub struct SceneUniforms<'a> {
+    pub model_to_world: ModelToWorldTransform,
+    // For normals, see `to_normal_transform`
+    pub normal_to_world: ModelToWorldTransform,
+    pub model_to_clip: ModelToClipTransform,
+    pub camera_position: WorldPoint,
+    pub lights: &'a [&'a dyn LightSource],
+}
+
+// A programmable shader program, the rest of the pipeline is fixed-function
+pub trait Shader {
+    // Per-vertex input, e.g. a model-space position with its normal and texture coordinates
+    type Attributes;
+    // Per-draw input on top of the scene uniforms, e.g. material parameters
+    type Uniforms;
+    // Vertex stage output, interpolated perspective-correctly across the triangle
+    type Varyings: Interpolate;
+
+    fn vertex(
+        &self,
+        attributes: &Self::Attributes,
+        uniforms: &Self::Uniforms,
+        scene: &SceneUniforms,
+    ) -> ClipVertex<Self::Varyings>;
+
+    // Returning `None` discards the fragment, leaving both the color and the depth untouched
+    fn fragment(
+        &self,
+        varyings: &Self::Varyings,
+        uniforms: &Self::Uniforms,
+        scene: &SceneUniforms,
+    ) -> Option<LinSrgb>;
+}
+
+// Anything that can be blended across a primitive, e.g. vertex attributes when a triangle gets
+// clipped or rasterized
+pub trait Interpolate: Copy {
+    fn scale(&self, factor: f32) -> Self;
+
+    fn sum(&self, other: &Self) -> Self;
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self.scale(1.0 - t).sum(&other.scale(t))
+    }
+
+    fn barycentric(a: &Self, b: &Self, c: &Self, weights: [f32; 3]) -> Self {
+        a.scale(weights[0])
+            .sum(&b.scale(weights[1]))
+            .sum(&c.scale(weights[2]))
+    }
+}
+
+impl Interpolate for () {
+    fn scale(&self, _factor: f32) -> Self {}
+
+    fn sum(&self, _other: &Self) -> Self {}
+}
+
+impl Interpolate for f32 {
+    fn scale(&self, factor: f32) -> Self {
+        self * factor
+    }
+
+    fn sum(&self, other: &Self) -> Self {
+        self + other
+    }
+}
+
+macro_rules! impl_interpolate_for_vector {
+    ($($vector:ident),*) => {
+        $(
+            impl<U: Unit<Scalar = f32>> Interpolate for $vector<U> {
+                fn scale(&self, factor: f32) -> Self {
+                    *self * factor
+                }
+
+                fn sum(&self, other: &Self) -> Self {
+                    *self + *other
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_for_vector!(Vector2, Vector3, Vector4);
+
+macro_rules! impl_interpolate_for_point {
+    ($($point:ident),*) => {
+        $(
+            impl<U: Unit<Scalar = f32>> Interpolate for $point<U> {
+                fn scale(&self, factor: f32) -> Self {
+                    Self::from_vector(self.to_vector() * factor)
+                }
+
+                fn sum(&self, other: &Self) -> Self {
+                    Self::from_vector(self.to_vector() + other.to_vector())
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_for_point!(Point2, Point3, Point4);
+
+macro_rules! impl_interpolate_for_color {
+    ($($color:ty),*) => {
+        $(
+            impl Interpolate for $color {
+                fn scale(&self, factor: f32) -> Self {
+                    *self * factor
+                }
+
+                fn sum(&self, other: &Self) -> Self {
+                    *self + *other
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_for_color!(LinSrgb, LinSrgba);
+
+macro_rules! impl_interpolate_for_tuple {
+    ($(($($name:ident: $idx:tt),*)),*) => {
+        $(
+            impl<$($name: Interpolate),*> Interpolate for ($($name,)*) {
+                fn scale(&self, factor: f32) -> Self {
+                    ($(self.$idx.scale(factor),)*)
+                }
+
+                fn sum(&self, other: &Self) -> Self {
+                    ($(self.$idx.sum(&other.$idx),)*)
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_for_tuple!(
+    (A: 0),
+    (A: 0, B: 1),
+    (A: 0, B: 1, C: 2),
+    (A: 0, B: 1, C: 2, D: 3)
+);
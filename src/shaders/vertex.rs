@@ -1,5 +1,40 @@
+use crate::common::space::HomogeneousClipPoint;
 use crate::shaders::traits::*;
 
+// Runs the vertex stage of a shader over a whole vertex buffer
 pub struct VertexShader;
 
-impl Shader for VertexShader {}
+impl VertexShader {
+    // The output is cleared first, so it can be reused from draw to draw without reallocating
+    pub fn process<S: Shader>(
+        &self,
+        shader: &S,
+        uniforms: &S::Uniforms,
+        scene: &SceneUniforms,
+        attributes: &[S::Attributes],
+        output: &mut Vec<ClipVertex<S::Varyings>>,
+    ) {
+        output.clear();
+        output.extend(
+            attributes
+                .iter()
+                .map(|vertex_attributes| shader.vertex(vertex_attributes, uniforms, scene)),
+        );
+    }
+}
+
+// A vertex after vertex processing, positioned in clip space before the perspective divide
+#[derive(Clone, Copy, Debug)]
+pub struct ClipVertex<V> {
+    pub position: HomogeneousClipPoint,
+    pub varyings: V,
+}
+
+impl<V: Interpolate> ClipVertex<V> {
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            position: self.position.lerp(other.position, t),
+            varyings: self.varyings.lerp(&other.varyings, t),
+        }
+    }
+}
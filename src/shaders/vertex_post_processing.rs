@@ -1,8 +1,310 @@
+use crate::common::primitives::PolygonPoints2;
+use crate::common::space::{
+    ClipPoint, ClipScalar, ClipSpace, ClipToScreenTransform, HomogeneousClipPoint, ScreenPoint,
+    ScreenScalar, ScreenSpace,
+};
 use crate::shaders::traits::*;
+use crate::shaders::vertex::ClipVertex;
+use glamour::{Vector2, Vector3};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FaceCulling {
+    #[default]
+    Back,
+    Front,
+    None,
+}
+
+// Order in which the vertices of a front-facing triangle appear on screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Winding {
+    Clockwise,
+    #[default]
+    CounterClockwise,
+}
+
+#[derive(Default)]
+pub struct CullingShader {
+    face_culling: FaceCulling,
+    front_face_winding: Winding,
+}
 
-pub struct CullingShader;
 pub struct ClippingShader;
 
-impl Shader for CullingShader {}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ClipPlane {
+    Left,
+    Right,
+    Bottom,
+    Top,
+    Near,
+    Far,
+}
+
+impl ClipPlane {
+    const ALL: [ClipPlane; 6] = [
+        ClipPlane::Left,
+        ClipPlane::Right,
+        ClipPlane::Bottom,
+        ClipPlane::Top,
+        ClipPlane::Near,
+        ClipPlane::Far,
+    ];
+
+    fn outcode_bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+
+    // Positive on the inside of the plane, i.e. for -w <= x, y, z <= w
+    fn distance(&self, position: &HomogeneousClipPoint) -> ClipScalar {
+        match self {
+            ClipPlane::Left => position.w + position.x,
+            ClipPlane::Right => position.w - position.x,
+            ClipPlane::Bottom => position.w + position.y,
+            ClipPlane::Top => position.w - position.y,
+            ClipPlane::Near => position.w + position.z,
+            ClipPlane::Far => position.w - position.z,
+        }
+    }
+}
+
+// Every clipping plane can add at most one vertex to the original three
+const MAX_CLIPPED_VERTICES: usize = 3 + ClipPlane::ALL.len();
+
+// Convex polygon left over after clipping a triangle, to be drawn as a fan around its first vertex
+#[derive(Clone, Copy, Debug)]
+pub struct TriangleFan<V> {
+    vertices: [ClipVertex<V>; MAX_CLIPPED_VERTICES],
+    len: usize,
+}
+
+impl<V: Interpolate> TriangleFan<V> {
+    fn from_triangle(triangle: [ClipVertex<V>; 3]) -> Self {
+        let mut vertices = [triangle[0]; MAX_CLIPPED_VERTICES];
+        vertices[..3].copy_from_slice(&triangle);
+
+        Self { vertices, len: 3 }
+    }
+
+    fn empty_like(&self) -> Self {
+        Self {
+            vertices: self.vertices,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, vertex: ClipVertex<V>) {
+        // Only rounding errors on nearly degenerate polygons could overflow, those can be dropped
+        if self.len < MAX_CLIPPED_VERTICES {
+            self.vertices[self.len] = vertex;
+            self.len += 1;
+        }
+    }
+
+    // Sutherland-Hodgman algorithm
+    fn clip_against(&self, plane: ClipPlane) -> Self {
+        let mut clipped = self.empty_like();
+
+        for idx in 0..self.len {
+            let current = &self.vertices[idx];
+            let next = &self.vertices[(idx + 1) % self.len];
+            let current_distance = plane.distance(&current.position);
+            let next_distance = plane.distance(&next.position);
+
+            if current_distance >= 0.0 {
+                clipped.push(*current);
+            }
+
+            // Always interpolate from the inside vertex so that an edge shared by two triangles
+            // gets clipped to exactly the same point in both
+            if current_distance >= 0.0 && next_distance < 0.0 {
+                let t = current_distance / (current_distance - next_distance);
+                clipped.push(current.lerp(next, t));
+            } else if current_distance < 0.0 && next_distance >= 0.0 {
+                let t = next_distance / (next_distance - current_distance);
+                clipped.push(next.lerp(current, t));
+            }
+        }
+
+        clipped
+    }
+
+    pub fn vertices(&self) -> &[ClipVertex<V>] {
+        &self.vertices[..self.len]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len < 3
+    }
+
+    pub fn triangles(&self) -> impl Iterator<Item = [ClipVertex<V>; 3]> + '_ {
+        (1..self.len.saturating_sub(1))
+            .map(|idx| [self.vertices[0], self.vertices[idx], self.vertices[idx + 1]])
+    }
+}
+
+fn outcode(position: &HomogeneousClipPoint) -> u8 {
+    ClipPlane::ALL
+        .iter()
+        .filter(|plane| plane.distance(position) < 0.0)
+        .fold(0, |code, plane| code | plane.outcode_bit())
+}
+
+impl CullingShader {
+    pub fn new(face_culling: FaceCulling, front_face_winding: Winding) -> Self {
+        Self {
+            face_culling,
+            front_face_winding,
+        }
+    }
+
+    pub fn face_culling(&self) -> FaceCulling {
+        self.face_culling
+    }
+
+    pub fn set_face_culling(&mut self, face_culling: FaceCulling) {
+        self.face_culling = face_culling;
+    }
+
+    pub fn front_face_winding(&self) -> Winding {
+        self.front_face_winding
+    }
+
+    pub fn set_front_face_winding(&mut self, front_face_winding: Winding) {
+        self.front_face_winding = front_face_winding;
+    }
+
+    // Meant to run before clipping, so that only the triangles that can end up on screen get clipped
+    pub fn cull<V>(&self, triangle: &[ClipVertex<V>; 3]) -> bool {
+        Self::is_outside_frustum(triangle) || self.is_culled_face(triangle)
+    }
+
+    // Catches triangles that only collapsed after the perspective divide and the viewport transform
+    pub fn cull_screen(&self, polygon: &PolygonPoints2) -> bool {
+        polygon.is_degenerate()
+    }
+
+    fn is_outside_frustum<V>(triangle: &[ClipVertex<V>; 3]) -> bool {
+        // All vertices are outside of the same plane
+        triangle
+            .iter()
+            .fold(u8::MAX, |code, vertex| code & outcode(&vertex.position))
+            != 0
+    }
+
+    // The determinant of the homogeneous (x, y, w) coordinates has the sign of the projected
+    // area even for vertices behind the camera, so no perspective divide is needed
+    fn is_culled_face<V>(&self, triangle: &[ClipVertex<V>; 3]) -> bool {
+        let [a, b, c] = triangle.each_ref().map(|vertex| {
+            Vector3::<ClipSpace>::new(vertex.position.x, vertex.position.y, vertex.position.w)
+        });
+        let orientation = a.dot(b.cross(c));
+
+        if orientation == 0.0 || orientation.is_nan() {
+            return true;
+        }
+
+        let is_counter_clockwise = orientation > 0.0;
+        let is_front_facing = match self.front_face_winding {
+            Winding::CounterClockwise => is_counter_clockwise,
+            Winding::Clockwise => !is_counter_clockwise,
+        };
+
+        match self.face_culling {
+            FaceCulling::Back => !is_front_facing,
+            FaceCulling::Front => is_front_facing,
+            FaceCulling::None => false,
+        }
+    }
+}
+
+impl ClippingShader {
+    pub fn clip_triangle<V: Interpolate>(&self, triangle: [ClipVertex<V>; 3]) -> TriangleFan<V> {
+        let outcodes = triangle.map(|vertex| outcode(&vertex.position));
+        let mut fan = TriangleFan::from_triangle(triangle);
+
+        if (outcodes[0] & outcodes[1] & outcodes[2]) != 0 {
+            // All vertices are outside of the same plane
+            return fan.empty_like();
+        }
+
+        let crossed_planes = outcodes[0] | outcodes[1] | outcodes[2];
+        for plane in ClipPlane::ALL {
+            if (crossed_planes & plane.outcode_bit()) != 0 {
+                fan = fan.clip_against(plane);
+                if fan.is_empty() {
+                    break;
+                }
+            }
+        }
+
+        fan
+    }
+}
+
+// A vertex after the perspective divide and the viewport transform, ready for rasterization
+#[derive(Clone, Copy, Debug)]
+pub struct ScreenVertex<V> {
+    pub position: ScreenPoint,
+    pub depth: ScreenScalar,
+    // Needed for perspective-correct interpolation of the varyings
+    pub inverse_w: ClipScalar,
+    pub varyings: V,
+}
+
+impl<V: Interpolate> ScreenVertex<V> {
+    // Screen-space weights have to be corrected by 1/w, as the varyings are only linear in clip space
+    pub fn interpolate_varyings(
+        triangle: &[ScreenVertex<V>; 3],
+        weights: Vector3<ScreenSpace>,
+    ) -> V {
+        let perspective_weights = [
+            weights.x * triangle[0].inverse_w,
+            weights.y * triangle[1].inverse_w,
+            weights.z * triangle[2].inverse_w,
+        ];
+        let normalization = perspective_weights.iter().sum::<f32>().recip();
+
+        V::barycentric(
+            &triangle[0].varyings,
+            &triangle[1].varyings,
+            &triangle[2].varyings,
+            perspective_weights.map(|weight| weight * normalization),
+        )
+    }
+}
+
+pub struct Viewport {
+    clip_to_screen: ClipToScreenTransform,
+}
+
+impl Viewport {
+    pub fn new(dimensions: Vector2<u32>) -> Self {
+        let half_width = dimensions.x as ScreenScalar / 2.0;
+        let half_height = dimensions.y as ScreenScalar / 2.0;
+
+        // NDC has Y pointing up while the screen has it pointing down, depth goes to [0, 1]
+        let clip_to_screen =
+            ClipToScreenTransform::from_scale(Vector3::new(half_width, -half_height, 0.5))
+                .then_translate(Vector3::new(half_width, half_height, 0.5));
+
+        Self { clip_to_screen }
+    }
+
+    pub fn to_screen<V: Copy>(&self, vertex: &ClipVertex<V>) -> ScreenVertex<V> {
+        let inverse_w = vertex.position.w.recip();
+        let ndc_point = ClipPoint::new(
+            vertex.position.x * inverse_w,
+            vertex.position.y * inverse_w,
+            vertex.position.z * inverse_w,
+        );
+        let screen_point = self.clip_to_screen.map_point(ndc_point);
 
-impl Shader for ClippingShader {}
+        ScreenVertex {
+            position: ScreenPoint::new(screen_point.x, screen_point.y),
+            depth: screen_point.z,
+            inverse_w,
+            varyings: vertex.varyings,
+        }
+    }
+}
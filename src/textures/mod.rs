@@ -0,0 +1,3 @@
+pub mod sampler;
+pub mod texture;
+pub mod tga;
@@ -0,0 +1,96 @@
+use crate::common::space::{TexturePoint, TextureScalar};
+use crate::textures::texture::{Texel, Texture};
+use palette::{LinSrgba, Mix};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    #[default]
+    Nearest,
+    Bilinear,
+}
+
+// What happens to texture coordinates outside of [0, 1]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WrapMode {
+    #[default]
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+impl WrapMode {
+    fn wrap(&self, texel_idx: i64, size: u32) -> u32 {
+        let size = size as i64;
+        let wrapped = match self {
+            WrapMode::Repeat => texel_idx.rem_euclid(size),
+            WrapMode::Clamp => texel_idx.clamp(0, size - 1),
+            WrapMode::Mirror => {
+                let period_idx = texel_idx.rem_euclid(2 * size);
+                if period_idx < size {
+                    period_idx
+                } else {
+                    2 * size - 1 - period_idx
+                }
+            }
+        };
+
+        wrapped as u32
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sampler {
+    pub filter: Filter,
+    pub wrap_u: WrapMode,
+    pub wrap_v: WrapMode,
+}
+
+impl Sampler {
+    pub fn new(filter: Filter, wrap: WrapMode) -> Self {
+        Self {
+            filter,
+            wrap_u: wrap,
+            wrap_v: wrap,
+        }
+    }
+
+    // (0, 0) is the bottom-left corner of the texture, as in OBJ files
+    pub fn sample<T: Texel>(
+        &self,
+        texture: &Texture<T>,
+        texture_coordinates: TexturePoint,
+    ) -> LinSrgba {
+        let dimensions = texture.dimensions();
+        let x = texture_coordinates.x * dimensions.x as TextureScalar;
+        let y = (1.0 - texture_coordinates.y) * dimensions.y as TextureScalar;
+
+        match self.filter {
+            Filter::Nearest => self.fetch(texture, x.floor() as i64, y.floor() as i64),
+            Filter::Bilinear => {
+                // Texel centers sit at +0.5
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (left, top) = (x.floor(), y.floor());
+                let (fract_x, fract_y) = (x - left, y - top);
+                let (left, top) = (left as i64, top as i64);
+
+                let upper = self
+                    .fetch(texture, left, top)
+                    .mix(self.fetch(texture, left + 1, top), fract_x);
+                let lower = self
+                    .fetch(texture, left, top + 1)
+                    .mix(self.fetch(texture, left + 1, top + 1), fract_x);
+                upper.mix(lower, fract_y)
+            }
+        }
+    }
+
+    fn fetch<T: Texel>(&self, texture: &Texture<T>, x: i64, y: i64) -> LinSrgba {
+        let dimensions = texture.dimensions();
+        texture
+            .get_texel(
+                self.wrap_u.wrap(x, dimensions.x),
+                self.wrap_v.wrap(y, dimensions.y),
+            )
+            .to_linear()
+    }
+}
@@ -0,0 +1,103 @@
+use crate::common::space::TexturePoint;
+use crate::textures::sampler::Sampler;
+use derive_more::Display;
+use glamour::Vector2;
+use palette::rgb::PackedArgb;
+use palette::{LinSrgb, LinSrgba, Srgb, Srgba, WithAlpha};
+
+// A color a texture can be stored in, sampling always filters in linear space
+pub trait Texel: Copy {
+    fn to_linear(self) -> LinSrgba;
+}
+
+impl Texel for LinSrgba {
+    fn to_linear(self) -> LinSrgba {
+        self
+    }
+}
+
+impl Texel for LinSrgb {
+    fn to_linear(self) -> LinSrgba {
+        self.with_alpha(1.0)
+    }
+}
+
+impl Texel for Srgba<u8> {
+    fn to_linear(self) -> LinSrgba {
+        self.into_linear()
+    }
+}
+
+impl Texel for Srgb<u8> {
+    fn to_linear(self) -> LinSrgba {
+        self.into_linear().with_alpha(1.0)
+    }
+}
+
+impl Texel for PackedArgb {
+    fn to_linear(self) -> LinSrgba {
+        Srgba::<u8>::from(self).into_linear()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum TextureError {
+    #[display("texture has no texels")]
+    Empty,
+    #[display("expected {expected} texels but got {actual}")]
+    SizeMismatch { expected: usize, actual: usize },
+}
+
+// Texels are stored row by row, starting with the top row
+#[derive(Clone, Debug)]
+pub struct Texture<T: Texel = LinSrgba> {
+    texels: Vec<T>,
+    width: u32,
+    height: u32,
+}
+
+impl<T: Texel> Texture<T> {
+    pub fn new(dimensions: Vector2<u32>, texels: Vec<T>) -> Result<Self, TextureError> {
+        let expected = (dimensions.x * dimensions.y) as usize;
+        if expected == 0 {
+            return Err(TextureError::Empty);
+        }
+        if texels.len() != expected {
+            return Err(TextureError::SizeMismatch {
+                expected,
+                actual: texels.len(),
+            });
+        }
+
+        Ok(Self {
+            texels,
+            width: dimensions.x,
+            height: dimensions.y,
+        })
+    }
+
+    // A single texel texture, every sample returns `texel`
+    pub fn solid(texel: T) -> Self {
+        Self {
+            texels: vec![texel],
+            width: 1,
+            height: 1,
+        }
+    }
+
+    pub fn dimensions(&self) -> Vector2<u32> {
+        Vector2::new(self.width, self.height)
+    }
+
+    pub fn texels(&self) -> &[T] {
+        &self.texels
+    }
+
+    pub fn get_texel(&self, x: u32, y: u32) -> T {
+        self.texels[(y * self.width + x) as usize]
+    }
+
+    pub fn sample(&self, sampler: &Sampler, texture_coordinates: TexturePoint) -> LinSrgba {
+        sampler.sample(self, texture_coordinates)
+    }
+}
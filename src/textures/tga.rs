@@ -0,0 +1,128 @@
+use crate::textures::texture::{Texture, TextureError};
+use derive_more::{Display, From};
+use glamour::Vector2;
+use palette::Srgba;
+use std::io::Read;
+
+#[derive(Debug, Display, From)]
+pub enum TgaError {
+    #[display("failed to read TGA data: {_0}")]
+    Io(std::io::Error),
+    #[display("unsupported TGA image type {_0}")]
+    #[from(ignore)]
+    UnsupportedImageType(u8),
+    #[display("unsupported TGA pixel depth of {_0} bits")]
+    #[from(ignore)]
+    UnsupportedPixelDepth(u8),
+    #[display("invalid TGA image: {_0}")]
+    Texture(TextureError),
+}
+
+const HEADER_LENGTH: usize = 18;
+
+// Image types without a color map, the RLE variants are offset by 8
+const TRUE_COLOR: u8 = 2;
+const GRAYSCALE: u8 = 3;
+const RLE_FLAG: u8 = 8;
+
+// Bits of the image descriptor byte
+const RIGHT_TO_LEFT: u8 = 1 << 4;
+const TOP_TO_BOTTOM: u8 = 1 << 5;
+
+// Reads uncompressed and RLE-compressed true color (24 and 32 bit) or grayscale (8 bit) images
+pub fn read_tga<R: Read>(mut reader: R) -> Result<Texture<Srgba<u8>>, TgaError> {
+    let mut header = [0u8; HEADER_LENGTH];
+    reader.read_exact(&mut header)?;
+
+    let id_length = header[0];
+    let image_type = header[2];
+    let width = u16::from_le_bytes([header[12], header[13]]) as u32;
+    let height = u16::from_le_bytes([header[14], header[15]]) as u32;
+    let pixel_depth = header[16];
+    let descriptor = header[17];
+
+    if header[1] != 0 || !matches!(image_type & !RLE_FLAG, TRUE_COLOR | GRAYSCALE) {
+        return Err(TgaError::UnsupportedImageType(image_type));
+    }
+    let bytes_per_pixel = match (image_type & !RLE_FLAG, pixel_depth) {
+        (TRUE_COLOR, 24) => 3,
+        (TRUE_COLOR, 32) => 4,
+        (GRAYSCALE, 8) => 1,
+        _ => return Err(TgaError::UnsupportedPixelDepth(pixel_depth)),
+    };
+
+    let mut id = vec![0u8; id_length as usize];
+    reader.read_exact(&mut id)?;
+
+    let pixel_count = (width * height) as usize;
+    let data = if image_type & RLE_FLAG != 0 {
+        read_rle_data(&mut reader, pixel_count, bytes_per_pixel)?
+    } else {
+        let mut data = vec![0u8; pixel_count * bytes_per_pixel];
+        reader.read_exact(&mut data)?;
+        data
+    };
+
+    let mut texels: Vec<Srgba<u8>> = data
+        .chunks_exact(bytes_per_pixel)
+        .map(|pixel| match *pixel {
+            [b, g, r, a] => Srgba::new(r, g, b, a),
+            [b, g, r] => Srgba::new(r, g, b, u8::MAX),
+            [l] => Srgba::new(l, l, l, u8::MAX),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    // Textures are stored top row first, left to right
+    if width > 0 {
+        if descriptor & RIGHT_TO_LEFT != 0 {
+            texels
+                .chunks_exact_mut(width as usize)
+                .for_each(|row| row.reverse());
+        }
+        if descriptor & TOP_TO_BOTTOM == 0 {
+            texels = texels
+                .chunks_exact(width as usize)
+                .rev()
+                .flatten()
+                .copied()
+                .collect();
+        }
+    }
+
+    Ok(Texture::new(Vector2::new(width, height), texels)?)
+}
+
+// Packets start with a header byte, the high bit marks a run of one repeated pixel, the low seven
+// bits hold the pixel count minus one
+fn read_rle_data<R: Read>(
+    reader: &mut R,
+    pixel_count: usize,
+    bytes_per_pixel: usize,
+) -> Result<Vec<u8>, TgaError> {
+    let mut data = Vec::with_capacity(pixel_count * bytes_per_pixel);
+    let mut pixel = [0u8; 4];
+    let pixel = &mut pixel[..bytes_per_pixel];
+
+    while data.len() < pixel_count * bytes_per_pixel {
+        let mut packet_header = [0u8; 1];
+        reader.read_exact(&mut packet_header)?;
+        let packet_length = (packet_header[0] & 0x7f) as usize + 1;
+
+        if packet_header[0] & 0x80 != 0 {
+            reader.read_exact(pixel)?;
+            for _ in 0..packet_length {
+                data.extend_from_slice(pixel);
+            }
+        } else {
+            for _ in 0..packet_length {
+                reader.read_exact(pixel)?;
+                data.extend_from_slice(pixel);
+            }
+        }
+    }
+
+    // A packet may run past the last pixel in broken files
+    data.truncate(pixel_count * bytes_per_pixel);
+    Ok(data)
+}
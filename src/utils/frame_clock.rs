@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Longest frame time fed into the simulation, so that a stall (loading, a debugger, a dragged
+// window) does not make everything jump ahead
+pub const MAX_DELTA: Duration = Duration::from_millis(250);
+
+// Frames kept for the rolling statistics, a few seconds' worth at handheld frame rates
+pub const STATS_WINDOW: usize = 300;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameMode {
+    // Renders as fast as possible, the delta is the measured frame time
+    Uncapped,
+    // Waits so that frames start no more often than `fps` times a second
+    Capped { fps: u32 },
+    // Renders as fast as possible, but the simulation advances in steps of `step`, as many per
+    // frame as have accumulated
+    FixedTimestep { step: Duration },
+}
+
+// What the application should simulate for the current frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tick {
+    // Time covered by one update
+    pub delta: Duration,
+    // Updates to run with `delta`, always 1 outside of fixed-timestep mode
+    pub steps: u32,
+    // How far into the next fixed step rendering is, in [0, 1), for interpolating between the
+    // last two simulation states
+    pub alpha: f32,
+}
+
+impl Tick {
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+}
+
+// Rolling frame time statistics over the last `capacity` frames
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    frame_times: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl FrameStats {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frame_times: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn record(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == self.capacity {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frame_times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame_times.is_empty()
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.is_empty() {
+            return Duration::ZERO;
+        }
+
+        self.frame_times.iter().sum::<Duration>() / self.len() as u32
+    }
+
+    // Average of the slowest 1% of frames, at least one, shows stutter that the average hides
+    pub fn one_percent_low(&self) -> Duration {
+        if self.is_empty() {
+            return Duration::ZERO;
+        }
+
+        let mut frame_times: Vec<Duration> = self.frame_times.iter().copied().collect();
+        frame_times.sort_unstable_by(|a, b| b.cmp(a));
+        let count = (frame_times.len() / 100).max(1);
+
+        frame_times[..count].iter().sum::<Duration>() / count as u32
+    }
+
+    pub fn worst(&self) -> Duration {
+        self.frame_times.iter().copied().max().unwrap_or_default()
+    }
+
+    pub fn fps(&self) -> f32 {
+        let average = self.average();
+        if average.is_zero() {
+            0.0
+        } else {
+            average.as_secs_f32().recip()
+        }
+    }
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let milliseconds = |duration: Duration| duration.as_secs_f64() * 1_000.0;
+        write!(
+            f,
+            "{:.1} fps, average {:.2} ms, 1% low {:.2} ms, worst {:.2} ms",
+            self.fps(),
+            milliseconds(self.average()),
+            milliseconds(self.one_percent_low()),
+            milliseconds(self.worst()),
+        )
+    }
+}
+
+// Paces frames and turns wall-clock time into simulation time. Call `tick` once at the start of
+// every frame.
+pub struct FrameClock {
+    mode: FrameMode,
+    last_tick: Instant,
+    // Earliest start of the next frame when capped
+    deadline: Instant,
+    accumulator: Duration,
+    stats: FrameStats,
+}
+
+impl FrameClock {
+    pub fn new(mode: FrameMode) -> Self {
+        let now = Instant::now();
+        Self {
+            mode,
+            last_tick: now,
+            deadline: now,
+            accumulator: Duration::ZERO,
+            stats: FrameStats::new(STATS_WINDOW),
+        }
+    }
+
+    pub fn mode(&self) -> FrameMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: FrameMode) {
+        self.mode = mode;
+        self.accumulator = Duration::ZERO;
+        self.deadline = self.last_tick;
+    }
+
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    // When the next frame may start, `None` if it may start right away. Event loops can wait on
+    // this instead of calling `wait`.
+    pub fn deadline(&self) -> Option<Instant> {
+        match self.mode {
+            FrameMode::Capped { .. } => Some(self.deadline),
+            FrameMode::Uncapped | FrameMode::FixedTimestep { .. } => None,
+        }
+    }
+
+    // Sleeps until the next frame may start
+    pub fn wait(&self) {
+        if let Some(deadline) = self.deadline() {
+            let now = Instant::now();
+            if deadline > now {
+                thread::sleep(deadline - now);
+            }
+        }
+    }
+
+    pub fn tick(&mut self) -> Tick {
+        self.tick_at(Instant::now())
+    }
+
+    // Same as `tick`, with the current time passed in
+    pub fn tick_at(&mut self, now: Instant) -> Tick {
+        let frame_time = now.saturating_duration_since(self.last_tick);
+        self.last_tick = now;
+        self.stats.record(frame_time);
+        let delta = frame_time.min(MAX_DELTA);
+
+        match self.mode {
+            FrameMode::Uncapped => Tick {
+                delta,
+                steps: 1,
+                alpha: 0.0,
+            },
+            FrameMode::Capped { fps } => {
+                // Keeps the cadence despite oversleeping, but does not try to catch up on frames
+                // that were missed
+                let period = Duration::from_secs(1) / fps.max(1);
+                self.deadline = if self.deadline + period > now {
+                    self.deadline + period
+                } else {
+                    now + period
+                };
+
+                Tick {
+                    delta,
+                    steps: 1,
+                    alpha: 0.0,
+                }
+            }
+            FrameMode::FixedTimestep { step } => {
+                let step = step.max(Duration::from_micros(1));
+                self.accumulator += delta;
+                let steps = (self.accumulator.as_nanos() / step.as_nanos()) as u32;
+                self.accumulator -= step * steps;
+
+                Tick {
+                    delta: step,
+                    steps,
+                    alpha: self.accumulator.as_secs_f32() / step.as_secs_f32(),
+                }
+            }
+        }
+    }
+}
@@ -1 +1,2 @@
+pub mod frame_clock;
 pub mod winit_app;
@@ -0,0 +1,224 @@
+use glam::Quat;
+use glamour::{Angle, Vector2};
+use sw_render::common::camera::{Camera, CameraPose, OrthographicCamera, PerspectiveCamera};
+use sw_render::common::space::{WorldPoint, WorldVector};
+use sw_render::lights::shadow_map::ShadowMap;
+use sw_render::pipeline::renderer::Renderer;
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "expected {expected}, got {actual}"
+    );
+}
+
+// Both cameras sit on the Z axis looking down -Z, so view depth is 5 - z
+fn perspective_camera() -> PerspectiveCamera {
+    PerspectiveCamera::new(
+        WorldPoint::new(0.0, 0.0, 5.0),
+        WorldVector::new(0.0, 0.0, -1.0),
+        1.0,
+        10.0,
+        90.0,
+        2.0,
+    )
+}
+
+fn orthographic_camera() -> OrthographicCamera {
+    OrthographicCamera::new(
+        WorldPoint::new(0.0, 0.0, 5.0),
+        WorldVector::new(0.0, 0.0, -1.0),
+        1.0,
+        10.0,
+        4.0,
+        2.0,
+    )
+}
+
+#[test]
+fn view_projection_combines_both_transforms() {
+    let cameras: [&dyn Camera; 2] = [&perspective_camera(), &orthographic_camera()];
+    let point = WorldPoint::new(0.3, -0.7, 1.5);
+    for camera in cameras {
+        let combined = camera.view_projection_transform().map_point(point);
+        let separate = camera
+            .projection_transform()
+            .map_point(camera.view_transform().map_point(point));
+        assert_close(combined.x, separate.x);
+        assert_close(combined.y, separate.y);
+        assert_close(combined.z, separate.z);
+    }
+}
+
+#[test]
+fn clip_planes_map_to_the_depth_range() {
+    let cameras: [&dyn Camera; 2] = [&perspective_camera(), &orthographic_camera()];
+    for camera in cameras {
+        let position = camera.position();
+        let near = WorldPoint::new(0.0, 0.0, position.z - camera.near_plane());
+        let far = WorldPoint::new(0.0, 0.0, position.z - camera.far_plane());
+        let world_to_clip = camera.view_projection_transform();
+        assert_close(world_to_clip.map_point(near).z, -1.0);
+        assert_close(world_to_clip.map_point(far).z, 1.0);
+    }
+}
+
+#[test]
+fn orthographic_sizes_do_not_depend_on_distance() {
+    let camera = orthographic_camera();
+    let world_to_clip = camera.view_projection_transform();
+
+    // A 4 high view at an aspect ratio of 2 spans 8 horizontally
+    for z in [3.0, 0.0, -4.0] {
+        let corner = world_to_clip.map_point(WorldPoint::new(4.0, 2.0, z));
+        assert_close(corner.x, 1.0);
+        assert_close(corner.y, 1.0);
+    }
+}
+
+#[test]
+fn orthographic_zoom_follows_the_view_height() {
+    let mut camera = orthographic_camera();
+    camera.set_view_height(8.0);
+    camera.set_aspect_ratio(1.0);
+
+    let corner = camera
+        .view_projection_transform()
+        .map_point(WorldPoint::new(4.0, 4.0, 0.0));
+    assert_close(corner.x, 1.0);
+    assert_close(corner.y, 1.0);
+}
+
+#[test]
+fn moving_updates_the_view() {
+    let mut camera = orthographic_camera();
+    camera.set_position(WorldPoint::new(1.0, 2.0, 5.0));
+    assert_eq!(camera.position(), WorldPoint::new(1.0, 2.0, 5.0));
+
+    let center = camera
+        .view_projection_transform()
+        .map_point(WorldPoint::new(1.0, 2.0, 0.0));
+    assert_close(center.x, 0.0);
+    assert_close(center.y, 0.0);
+}
+
+#[test]
+fn shadow_passes_take_any_camera() {
+    let renderer = Renderer::new();
+    let mut shadow_map = ShadowMap::new(Vector2::new(16, 16));
+    let camera = orthographic_camera();
+    renderer.begin_shadow_pass_from_camera(&mut shadow_map, &camera);
+
+    assert_eq!(
+        shadow_map.world_to_clip().matrix,
+        camera.view_projection_transform().matrix
+    );
+}
+
+fn assert_vector_close(actual: WorldVector, expected: WorldVector) {
+    assert!(
+        (actual - expected).length() < 1e-4,
+        "expected {expected:?}, got {actual:?}"
+    );
+}
+
+fn assert_orthonormal(pose: &CameraPose) {
+    let (forward, up, right) = (pose.forward(), pose.up(), pose.right());
+    for axis in [forward, up, right] {
+        assert!(axis.x.is_finite() && axis.y.is_finite() && axis.z.is_finite());
+        assert_close(axis.length(), 1.0);
+    }
+    assert_close(forward.dot(up), 0.0);
+    assert_close(forward.dot(right), 0.0);
+    assert_close(up.dot(right), 0.0);
+}
+
+#[test]
+fn looking_straight_down_stays_finite() {
+    let mut camera = perspective_camera();
+    camera.set_position(WorldPoint::new(0.0, 5.0, 0.0));
+    camera.look_at_point(&WorldPoint::ZERO);
+
+    assert_vector_close(camera.forward(), WorldVector::new(0.0, -1.0, 0.0));
+    assert_orthonormal(camera.pose());
+    // The previous up, +Y, could not be kept, so the previous forward takes its place
+    assert_vector_close(camera.up(), WorldVector::new(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn passing_over_the_target_keeps_the_view_steady() {
+    let mut camera = perspective_camera();
+    let mut previous_up = camera.up();
+    for step in 0..=36 {
+        let angle = (step as f32 * 5.0).to_radians();
+        camera.set_position(WorldPoint::new(0.0, 5.0 * angle.sin(), 5.0 * angle.cos()));
+        camera.look_at_point_with_up(&WorldPoint::ZERO, previous_up);
+
+        assert_orthonormal(camera.pose());
+        assert!(camera.up().dot(previous_up) > 0.9);
+        previous_up = camera.up();
+    }
+
+    // Upside down on the far side, as a loop would leave it
+    assert_vector_close(camera.up(), WorldVector::new(0.0, -1.0, 0.0));
+}
+
+#[test]
+fn yaw_pitch_and_roll_turn_the_view() {
+    let mut camera = orthographic_camera();
+    let pose = camera.pose_mut();
+
+    pose.set_yaw(Angle::from_degrees(90.0));
+    assert_vector_close(pose.forward(), WorldVector::new(-1.0, 0.0, 0.0));
+
+    pose.set_pitch(Angle::from_degrees(30.0));
+    let (sin, cos) = 30.0f32.to_radians().sin_cos();
+    assert_vector_close(pose.forward(), WorldVector::new(-cos, sin, 0.0));
+
+    pose.set_roll(Angle::from_degrees(90.0));
+    assert_vector_close(pose.forward(), WorldVector::new(-cos, sin, 0.0));
+    assert_vector_close(pose.up(), WorldVector::new(0.0, 0.0, 1.0));
+
+    let (yaw, pitch, roll) = pose.yaw_pitch_roll();
+    assert_close(yaw.to_degrees(), 90.0);
+    assert_close(pitch.to_degrees(), 30.0);
+    assert_close(roll.to_degrees(), 90.0);
+}
+
+#[test]
+fn world_up_can_be_changed() {
+    let mut pose = CameraPose::new(
+        WorldPoint::new(5.0, 0.0, 0.0),
+        WorldVector::new(-1.0, 0.0, 0.0),
+    );
+    pose.set_world_up(WorldVector::Z);
+    assert_vector_close(pose.forward(), WorldVector::new(-1.0, 0.0, 0.0));
+    assert_vector_close(pose.up(), WorldVector::Z);
+
+    pose.set_position(WorldPoint::new(5.0, 5.0, 2.0));
+    pose.look_at_point(&WorldPoint::ZERO);
+    assert_orthonormal(&pose);
+    assert!(pose.up().z > 0.9);
+    assert_close(pose.right().z, 0.0);
+
+    // Yaw turns around the world up
+    let pitch = pose.pitch();
+    pose.set_yaw(pose.yaw() + Angle::from_degrees(45.0));
+    assert_close(pose.pitch().radians, pitch.radians);
+    assert_close(pose.right().z, 0.0);
+}
+
+#[test]
+fn orientation_drives_the_view_transform() {
+    let mut camera = perspective_camera();
+    camera
+        .pose_mut()
+        .set_orientation(Quat::from_rotation_y(90f32.to_radians()));
+
+    // Now looking down -X, a point there lands in the center of the view
+    let center = camera
+        .view_projection_transform()
+        .map_point(WorldPoint::new(-3.0, 0.0, 5.0));
+    assert_close(center.x, 0.0);
+    assert_close(center.y, 0.0);
+}
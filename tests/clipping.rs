@@ -0,0 +1,97 @@
+use sw_render::common::space::HomogeneousClipPoint;
+use sw_render::shaders::vertex::ClipVertex;
+use sw_render::shaders::vertex_post_processing::ClippingShader;
+
+fn vertex(x: f32, y: f32, z: f32, w: f32, varying: f32) -> ClipVertex<f32> {
+    ClipVertex {
+        position: HomogeneousClipPoint::new(x, y, z, w),
+        varyings: varying,
+    }
+}
+
+fn assert_vertex(actual: &ClipVertex<f32>, expected: &ClipVertex<f32>) {
+    assert!(
+        (actual.position - expected.position).length() < 1e-5
+            && (actual.varyings - expected.varyings).abs() < 1e-5,
+        "expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[test]
+fn triangles_inside_are_kept_as_they_are() {
+    let triangle = [
+        vertex(-0.5, -0.5, 0.0, 1.0, 0.0),
+        vertex(0.5, -0.5, 0.5, 1.0, 1.0),
+        vertex(0.0, 0.5, -0.5, 1.0, 2.0),
+    ];
+    let fan = ClippingShader.clip_triangle(triangle);
+
+    assert_eq!(fan.vertices().len(), 3);
+    for (actual, expected) in fan.vertices().iter().zip(&triangle) {
+        assert_vertex(actual, expected);
+    }
+    assert_eq!(fan.triangles().count(), 1);
+}
+
+#[test]
+fn one_vertex_behind_the_near_plane_leaves_a_quad() {
+    // `b` is 2 behind the near plane, where w + z = 0, while `a` and `c` are 1 in front of it
+    let a = vertex(0.0, 0.0, 0.0, 1.0, 0.0);
+    let b = vertex(0.6, 0.0, -3.0, 1.0, 3.0);
+    let c = vertex(0.0, 0.6, 0.0, 1.0, 6.0);
+    let fan = ClippingShader.clip_triangle([a, b, c]);
+
+    // Both new vertices are a third of the way from the vertex in front to `b`
+    let expected = [
+        a,
+        vertex(0.2, 0.0, -1.0, 1.0, 1.0),
+        vertex(0.2, 0.4, -1.0, 1.0, 5.0),
+        c,
+    ];
+    assert_eq!(fan.vertices().len(), expected.len());
+    for (actual, expected) in fan.vertices().iter().zip(&expected) {
+        assert_vertex(actual, expected);
+        assert!(actual.position.w + actual.position.z >= -1e-5);
+    }
+    assert_eq!(fan.triangles().count(), 2);
+}
+
+#[test]
+fn two_vertices_behind_the_near_plane_leave_a_triangle() {
+    let a = vertex(0.0, 0.0, 0.0, 1.0, 0.0);
+    let b = vertex(0.6, 0.0, -3.0, 1.0, 3.0);
+    let c = vertex(0.0, 0.6, -5.0, 1.0, 6.0);
+    let fan = ClippingShader.clip_triangle([a, b, c]);
+
+    // The edge to `b` leaves the inside at a third of its length, the one to `c` at a fifth
+    let expected = [
+        a,
+        vertex(0.2, 0.0, -1.0, 1.0, 1.0),
+        vertex(0.0, 0.12, -1.0, 1.0, 1.2),
+    ];
+    assert_eq!(fan.vertices().len(), expected.len());
+    for (actual, expected) in fan.vertices().iter().zip(&expected) {
+        assert_vertex(actual, expected);
+    }
+}
+
+#[test]
+fn triangles_fully_outside_are_dropped() {
+    // Behind the near plane
+    let behind = ClippingShader.clip_triangle([
+        vertex(0.0, 0.0, -2.0, 1.0, 0.0),
+        vertex(0.5, 0.0, -3.0, 1.0, 0.0),
+        vertex(0.0, 0.5, -1.5, 1.0, 0.0),
+    ]);
+    assert!(behind.is_empty());
+    assert_eq!(behind.triangles().count(), 0);
+
+    // Past a corner of the frustum, with no single plane all three vertices are outside of
+    let corner = ClippingShader.clip_triangle([
+        vertex(1.6, 0.6, 0.0, 1.0, 0.0),
+        vertex(3.0, 3.0, 0.0, 1.0, 0.0),
+        vertex(0.6, 1.6, 0.0, 1.0, 0.0),
+    ]);
+    assert!(corner.is_empty());
+    assert_eq!(corner.triangles().count(), 0);
+}
@@ -0,0 +1,237 @@
+use glamour::{Angle, Vector2};
+use sw_render::common::camera::CameraPose;
+use sw_render::common::space::{WorldPoint, WorldVector};
+use sw_render::controls::fly::FlyController;
+use sw_render::controls::input::{Action, InputState};
+use sw_render::controls::orbit::OrbitController;
+use sw_render::controls::traits::CameraController;
+use sw_render::controls::winit_input::key_action;
+use winit::keyboard::{Key, NamedKey, SmolStr};
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "expected {expected}, got {actual}"
+    );
+}
+
+fn assert_vector_close(actual: WorldVector, expected: WorldVector) {
+    assert!(
+        (actual - expected).length() < 1e-3,
+        "expected {expected:?}, got {actual:?}"
+    );
+}
+
+fn pose_at(position: WorldPoint) -> CameraPose {
+    let mut pose = CameraPose::new(position, WorldVector::new(0.0, 0.0, -1.0));
+    pose.look_at_point(&WorldPoint::ZERO);
+    pose
+}
+
+#[test]
+fn input_state_tracks_actions_and_deltas() {
+    let mut input = InputState::new();
+    input.press(Action::MoveForward);
+    input.press(Action::TurnLeft);
+    input.press(Action::TurnRight);
+    assert_eq!(input.axis(Action::MoveBackward, Action::MoveForward), 1.0);
+    assert_eq!(input.axis(Action::TurnLeft, Action::TurnRight), 0.0);
+
+    input.release(Action::TurnRight);
+    assert_eq!(input.axis(Action::TurnLeft, Action::TurnRight), -1.0);
+
+    input.add_pointer_motion(Vector2::new(3.0, -2.0));
+    input.add_pointer_motion(Vector2::new(1.0, 1.0));
+    input.add_scroll(1.5);
+    assert_eq!(input.pointer_delta(), Vector2::new(4.0, -1.0));
+    assert_eq!(input.scroll_delta(), 1.5);
+
+    // Deltas belong to one frame, held actions stay held
+    input.end_frame();
+    assert_eq!(input.pointer_delta(), Vector2::ZERO);
+    assert_eq!(input.scroll_delta(), 0.0);
+    assert!(input.is_pressed(Action::MoveForward));
+
+    input.release_all();
+    assert!(!input.is_pressed(Action::MoveForward));
+}
+
+#[test]
+fn keys_map_to_actions() {
+    assert_eq!(
+        key_action(&Key::Character(SmolStr::new("W"))),
+        Some(Action::MoveForward)
+    );
+    assert_eq!(
+        key_action(&Key::Named(NamedKey::ArrowLeft)),
+        Some(Action::TurnLeft)
+    );
+    assert_eq!(key_action(&Key::Character(SmolStr::new("x"))), None);
+}
+
+#[test]
+fn orbiting_does_not_drift() {
+    let mut pose = pose_at(WorldPoint::new(0.0, 1.0, 3.0));
+    let mut orbit = OrbitController::from_pose(&pose, WorldPoint::ZERO);
+    let distance = orbit.distance;
+    orbit.auto_rotate_speed = Angle::from_degrees(7.5);
+
+    let input = InputState::new();
+    for _ in 0..100_000 {
+        orbit.update(&input, 1.0 / 60.0, &mut pose);
+    }
+
+    assert_close((pose.position() - WorldPoint::ZERO).length(), distance);
+    assert_close(pose.position().y, 1.0);
+    assert_vector_close(
+        pose.forward(),
+        (WorldPoint::ZERO - pose.position()).normalize(),
+    );
+}
+
+#[test]
+fn orbit_picks_up_from_the_pose() {
+    let position = WorldPoint::new(2.0, 1.5, -1.0);
+    let mut pose = pose_at(position);
+    let mut orbit = OrbitController::from_pose(&pose, WorldPoint::ZERO);
+    orbit.apply(&mut pose);
+
+    assert_vector_close(pose.position().to_vector(), position.to_vector());
+}
+
+#[test]
+fn orbit_turns_and_zooms() {
+    let mut pose = pose_at(WorldPoint::new(0.0, 0.0, 4.0));
+    let mut orbit = OrbitController::from_pose(&pose, WorldPoint::ZERO);
+
+    // A quarter turn to the right in one second
+    let mut input = InputState::new();
+    input.press(Action::TurnRight);
+    orbit.turn_speed = Angle::from_degrees(90.0);
+    orbit.update(&input, 1.0, &mut pose);
+    assert_vector_close(pose.position().to_vector(), WorldVector::new(4.0, 0.0, 0.0));
+
+    // Holding up for long stops short of the pole
+    let mut input = InputState::new();
+    input.press(Action::TurnUp);
+    orbit.update(&input, 10.0, &mut pose);
+    assert_close(orbit.elevation.to_degrees(), 89.0);
+    assert!(pose.forward().y < -0.99);
+
+    // One wheel line closer
+    let mut input = InputState::new();
+    input.add_scroll(1.0);
+    orbit.update(&input, 0.0, &mut pose);
+    assert_close(orbit.distance, 3.6);
+
+    orbit.min_distance = 1.0;
+    let mut input = InputState::new();
+    input.press(Action::ZoomIn);
+    orbit.update(&input, 100.0, &mut pose);
+    assert_close(orbit.distance, 1.0);
+}
+
+#[test]
+fn orbit_follows_the_world_up() {
+    let mut pose = CameraPose::new(
+        WorldPoint::new(4.0, 0.0, 0.0),
+        WorldVector::new(-1.0, 0.0, 0.0),
+    );
+    pose.set_world_up(WorldVector::Z);
+    let mut orbit = OrbitController::from_pose(&pose, WorldPoint::ZERO);
+
+    let mut input = InputState::new();
+    input.press(Action::TurnLeft);
+    for _ in 0..10 {
+        orbit.update(&input, 0.1, &mut pose);
+        assert_close(pose.position().z, 0.0);
+        assert_vector_close(pose.up(), WorldVector::Z);
+    }
+}
+
+#[test]
+fn flying_moves_along_the_view() {
+    let mut pose = CameraPose::new(WorldPoint::ZERO, WorldVector::new(0.0, 0.0, -1.0));
+    let mut fly = FlyController::from_pose(&pose);
+    fly.speed = 2.0;
+
+    let mut input = InputState::new();
+    input.press(Action::MoveForward);
+    fly.update(&input, 0.5, &mut pose);
+    assert_vector_close(
+        pose.position().to_vector(),
+        WorldVector::new(0.0, 0.0, -1.0),
+    );
+
+    // A quarter turn to the left looks down -X, moving up follows the world up
+    let mut input = InputState::new();
+    input.press(Action::TurnLeft);
+    fly.turn_speed = Angle::from_degrees(90.0);
+    fly.update(&input, 1.0, &mut pose);
+    assert_vector_close(pose.forward(), WorldVector::new(-1.0, 0.0, 0.0));
+
+    let mut input = InputState::new();
+    input.press(Action::MoveUp);
+    fly.update(&input, 0.5, &mut pose);
+    assert_vector_close(
+        pose.position().to_vector(),
+        WorldVector::new(0.0, 1.0, -1.0),
+    );
+}
+
+#[test]
+fn flying_cannot_look_past_straight_up() {
+    let mut pose = CameraPose::new(WorldPoint::ZERO, WorldVector::new(0.0, 0.0, -1.0));
+    let mut fly = FlyController::from_pose(&pose);
+
+    let mut input = InputState::new();
+    input.add_pointer_motion(Vector2::new(0.0, -10_000.0));
+    fly.update(&input, 0.0, &mut pose);
+
+    assert_close(fly.pitch.to_degrees(), 89.0);
+    assert!(pose.forward().y > 0.99);
+    assert!(pose.up().y > 0.0);
+}
+
+#[test]
+fn flying_rolls() {
+    let mut pose = CameraPose::new(WorldPoint::ZERO, WorldVector::new(0.0, 0.0, -1.0));
+    let mut fly = FlyController::from_pose(&pose);
+    fly.roll_speed = Angle::from_degrees(90.0);
+
+    let mut input = InputState::new();
+    input.press(Action::RollLeft);
+    fly.update(&input, 1.0, &mut pose);
+
+    assert_vector_close(pose.forward(), WorldVector::new(0.0, 0.0, -1.0));
+    assert_vector_close(pose.up(), WorldVector::new(-1.0, 0.0, 0.0));
+}
+
+#[test]
+fn pointer_deltas_count_once_however_many_steps_a_frame_takes() {
+    let mut input = InputState::new();
+    input.press(Action::TurnRight);
+    input.add_pointer_motion(Vector2::new(-30.0, 0.0));
+    input.add_scroll(1.0);
+
+    let held = input.held();
+    assert!(held.is_pressed(Action::TurnRight));
+    assert_eq!(held.pointer_delta(), Vector2::ZERO);
+    assert_eq!(held.scroll_delta(), 0.0);
+
+    // The way the demo updates: the deltas once, then each step with only the held actions
+    for steps in [0, 1, 3] {
+        let mut pose = pose_at(WorldPoint::new(0.0, 0.0, 4.0));
+        let mut orbit = OrbitController::from_pose(&pose, WorldPoint::ZERO);
+        orbit.turn_speed = Angle::from_degrees(10.0);
+        orbit.pointer_sensitivity = Angle::from_degrees(1.0);
+        orbit.update(&input, 0.0, &mut pose);
+        for _ in 0..steps {
+            orbit.update(&held, 1.0, &mut pose);
+        }
+
+        // 30° from dragging, 10° per step from holding
+        assert_close(orbit.azimuth.to_degrees(), 30.0 + 10.0 * steps as f32);
+        assert_close(orbit.distance, 3.6);
+    }
+}
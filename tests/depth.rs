@@ -0,0 +1,85 @@
+use glamour::Vector2;
+use sw_render::buffers::depth::{DepthBuffer, DepthCompare};
+use sw_render::common::primitives::PolygonPoints2;
+use sw_render::common::space::ScreenPoint;
+
+#[test]
+fn compare_functions() {
+    // Incoming depth against a stored one, nearer, equal and further
+    let cases = [
+        (DepthCompare::Less, [true, false, false]),
+        (DepthCompare::LessEqual, [true, true, false]),
+        (DepthCompare::Greater, [false, false, true]),
+        (DepthCompare::Always, [true, true, true]),
+        (DepthCompare::Never, [false, false, false]),
+    ];
+
+    for (compare, expected) in cases {
+        let passes = [0.25, 0.5, 0.75].map(|depth| compare.passes(depth, 0.5));
+        assert_eq!(passes, expected, "{compare:?}");
+
+        let mut depth_buffer = DepthBuffer::new(Vector2::new(3, 1));
+        depth_buffer.set_compare(compare);
+        depth_buffer.clear_to(0.5);
+        let written = [0.25, 0.5, 0.75]
+            .into_iter()
+            .enumerate()
+            .map(|(x, depth)| depth_buffer.test_and_write(x as u32, 0, depth))
+            .collect::<Vec<_>>();
+        assert_eq!(written, expected, "{compare:?}");
+
+        // Only the depths that passed are stored
+        for (x, (depth, passed)) in [0.25, 0.5, 0.75].into_iter().zip(expected).enumerate() {
+            let stored = depth_buffer.get_depth(x as u32, 0).unwrap();
+            assert_eq!(stored, if passed { depth } else { 0.5 }, "{compare:?}");
+        }
+    }
+}
+
+#[test]
+fn disabled_writes_still_test() {
+    let mut depth_buffer = DepthBuffer::new(Vector2::new(2, 2));
+    depth_buffer.set_write_enabled(false);
+
+    assert!(depth_buffer.test_and_write(0, 0, 0.3));
+    assert_eq!(depth_buffer.get_depth(0, 0), Some(DepthBuffer::FAR_DEPTH));
+
+    depth_buffer.fill_triangle(
+        &PolygonPoints2::new([
+            ScreenPoint::new(0.0, 0.0),
+            ScreenPoint::new(2.0, 0.0),
+            ScreenPoint::new(0.0, 2.0),
+        ]),
+        [0.1; 3],
+    );
+    assert_eq!(depth_buffer.get_depth(0, 0), Some(DepthBuffer::FAR_DEPTH));
+
+    // Turned back on, the same write lands
+    depth_buffer.set_write_enabled(true);
+    assert!(depth_buffer.test_and_write(0, 0, 0.3));
+    assert_eq!(depth_buffer.get_depth(0, 0), Some(0.3));
+}
+
+#[test]
+fn clears_to_custom_values() {
+    let mut depth_buffer = DepthBuffer::new(Vector2::new(2, 2));
+    depth_buffer.set_compare(DepthCompare::Greater);
+    depth_buffer.set_write_enabled(false);
+
+    // Reversed depth, where 0.0 is the far plane
+    depth_buffer.clear_to(0.0);
+    for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+        assert_eq!(depth_buffer.get_depth(x, y), Some(0.0));
+    }
+    assert!(depth_buffer.test(1, 1, 0.1));
+
+    // Clearing leaves the compare function and write mask alone
+    depth_buffer.clear();
+    assert_eq!(depth_buffer.get_depth(1, 1), Some(DepthBuffer::FAR_DEPTH));
+    assert_eq!(depth_buffer.compare(), DepthCompare::Greater);
+    assert!(!depth_buffer.write_enabled());
+
+    // Out of range pixels never pass
+    assert_eq!(depth_buffer.get_depth(2, 0), None);
+    assert!(!depth_buffer.test(0, 2, 0.5));
+}
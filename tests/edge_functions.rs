@@ -0,0 +1,151 @@
+use glamour::Vector3;
+use sw_render::common::edge_functions::EdgeFunctions;
+use sw_render::common::space::{ScreenPoint, ScreenSpace};
+
+type Fragment = (u32, u32, [u32; 3]);
+
+// Xorshift, so that every run sees the same triangles
+struct Random(u64);
+
+impl Random {
+    fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    fn point(&mut self, min: f32, max: f32) -> ScreenPoint {
+        ScreenPoint::new(self.range(min, max), self.range(min, max))
+    }
+}
+
+// Weights as bits, the block path has to match the scalar one exactly
+fn fragments(
+    rasterize: impl FnOnce(&mut dyn FnMut(u32, u32, Vector3<ScreenSpace>)),
+) -> Vec<Fragment> {
+    let mut fragments = vec![];
+    rasterize(&mut |x, y, weights| {
+        fragments.push((x, y, [weights.x, weights.y, weights.z].map(f32::to_bits)))
+    });
+    fragments.sort_unstable_by_key(|&(x, y, _)| (y, x));
+    fragments
+}
+
+// Returns how many pixels the triangle covers
+fn assert_paths_agree(points: [ScreenPoint; 3], width: u32, height: u32) -> usize {
+    let mut covered = 0;
+    for points in [points, [points[0], points[2], points[1]]] {
+        let edge_functions = EdgeFunctions::new(&points);
+        let block = fragments(|f| edge_functions.rasterize(width, height, f));
+        let scalar = fragments(|f| edge_functions.rasterize_scalar(width, height, f));
+        assert_eq!(block, scalar, "paths disagree on {points:?}");
+
+        // Probing every pixel center on its own has to find the same pixels
+        let mut probed = vec![];
+        for y in 0..height {
+            for x in 0..width {
+                let center = ScreenPoint::new(x as f32 + 0.5, y as f32 + 0.5);
+                if let Some(weights) = edge_functions.coverage(center) {
+                    probed.push((x, y, [weights.x, weights.y, weights.z].map(f32::to_bits)));
+                }
+            }
+        }
+        assert_eq!(block, probed, "coverage disagrees on {points:?}");
+        covered = block.len();
+    }
+
+    covered
+}
+
+#[test]
+fn block_rasterization_matches_scalar_rasterization() {
+    let mut random = Random(0x5eed_1234_abcd_ef01);
+    let mut covered = 0;
+    for _ in 0..500 {
+        let points = [(); 3].map(|_| random.point(-8.0, 72.0));
+        covered += assert_paths_agree(points, 64, 64);
+    }
+    assert!(covered > 0);
+}
+
+#[test]
+fn subpixel_slivers_match() {
+    let mut random = Random(0x511_7e55);
+    let mut covered = 0;
+    for _ in 0..500 {
+        // Two corners close together, the third anywhere, so the triangle is a thin needle
+        let tip = random.point(0.0, 32.0);
+        let base = random.point(-4.0, 36.0);
+        let offset = ScreenPoint::new(random.range(-0.2, 0.2), random.range(-0.2, 0.2));
+        let base_end = ScreenPoint::new(base.x + offset.x, base.y + offset.y);
+        covered += assert_paths_agree([tip, base, base_end], 32, 32);
+    }
+    assert!(covered > 0);
+}
+
+#[test]
+fn triangles_near_the_32_bit_limit_match() {
+    // Spanning up to 1600 pixels, still within the 2048 the block path handles
+    let mut random = Random(0x1a_26e5);
+    let mut covered = 0;
+    for _ in 0..100 {
+        let points = [(); 3].map(|_| random.point(-700.0, 900.0));
+        covered += assert_paths_agree(points, 96, 96);
+    }
+    assert!(covered > 0);
+}
+
+#[test]
+fn huge_triangles_fall_back_to_64_bits() {
+    // Corners a million pixels away overflow 32-bit edge functions even inside a small target
+    let mut random = Random(0xb16_b16);
+    let mut covered = 0;
+    for _ in 0..100 {
+        let points = [(); 3].map(|_| random.point(-1.0e6, 1.0e6));
+        covered += assert_paths_agree(points, 48, 48);
+    }
+    assert!(covered > 0);
+
+    // And one that certainly covers the whole target
+    let covered = assert_paths_agree(
+        [
+            ScreenPoint::new(-1.0e6, -1.0e6),
+            ScreenPoint::new(1.0e6, -1.0e6),
+            ScreenPoint::new(0.0, 1.0e6),
+        ],
+        48,
+        48,
+    );
+    assert_eq!(covered, 48 * 48);
+}
+
+#[test]
+fn far_away_corners_do_not_overflow_64_bits() {
+    // Products of coordinates this large would need around 90 bits, they get clamped instead
+    for extent in [1.0e12, 1.0e20, f32::MAX] {
+        let covered = assert_paths_agree(
+            [
+                ScreenPoint::new(-extent, -extent),
+                ScreenPoint::new(extent, -extent),
+                ScreenPoint::new(0.0, extent),
+            ],
+            16,
+            16,
+        );
+        assert_eq!(covered, 16 * 16, "{extent}");
+    }
+
+    // A sliver reaching that far keeps its orientation, crossing the target between y = 8.3 and
+    // about y = 8.6, over the centers of row 8
+    let sliver = [
+        ScreenPoint::new(-1.0e12, 8.3),
+        ScreenPoint::new(1.0e12, 8.3),
+        ScreenPoint::new(1.0e12, 8.9),
+    ];
+    assert_eq!(assert_paths_agree(sliver, 16, 16), 16);
+}
@@ -0,0 +1,199 @@
+use glamour::Vector2;
+use palette::Srgb;
+use std::io::ErrorKind;
+use sw_render::buffers::encoders::write_tga;
+use sw_render::buffers::image::{Image, ImageFormat};
+use sw_render::buffers::pixel_format::{PixelFormat, Xrgb8888};
+use sw_render::textures::tga::read_tga;
+
+// 5 pixels wide so BMP rows need padding, with a run in the top row for TGA's RLE packets
+fn test_image() -> Image {
+    let mut image = Image::new(Vector2::new(5, 3));
+    for (idx, pixel) in image.data_mut().iter_mut().enumerate() {
+        let color = if idx < 4 {
+            Srgb::new(200, 100, 50)
+        } else {
+            Srgb::new(idx as u8 * 10, 255 - idx as u8, idx as u8)
+        };
+        *pixel = Xrgb8888::pack(color);
+    }
+    image
+}
+
+fn encode(image: &Image, format: ImageFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    image.write(&mut bytes, format).unwrap();
+    bytes
+}
+
+fn u32_le(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u32_be(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn ppm_header_and_size() {
+    let image = test_image();
+    let bytes = encode(&image, ImageFormat::Ppm);
+
+    let header = b"P6\n5 3\n255\n";
+    assert_eq!(&bytes[..header.len()], header);
+    assert_eq!(bytes.len(), header.len() + 5 * 3 * 3);
+
+    let pixels: Vec<Srgb<u8>> = bytes[header.len()..]
+        .chunks(3)
+        .map(|rgb| Srgb::new(rgb[0], rgb[1], rgb[2]))
+        .collect();
+    assert!(pixels.iter().copied().eq(image.pixels()));
+}
+
+#[test]
+fn bmp_header_and_size() {
+    let image = test_image();
+    let bytes = encode(&image, ImageFormat::Bmp);
+
+    // 15 bytes of pixels per row, padded to 16
+    let row_length = 16;
+    assert_eq!(&bytes[..2], b"BM");
+    assert_eq!(u32_le(&bytes, 2) as usize, bytes.len());
+    assert_eq!(bytes.len(), 54 + row_length * 3);
+    assert_eq!(u32_le(&bytes, 10), 54);
+    assert_eq!(u32_le(&bytes, 14), 40);
+    assert_eq!(u32_le(&bytes, 18), 5);
+    assert_eq!(u32_le(&bytes, 22), 3);
+    assert_eq!(u16::from_le_bytes([bytes[28], bytes[29]]), 24);
+    assert_eq!(u32_le(&bytes, 34) as usize, row_length * 3);
+
+    // Bottom row first, BGR, zero padding
+    for (row, y) in bytes[54..].chunks(row_length).zip((0..3).rev()) {
+        for x in 0..5 {
+            let pixel = image.get_pixel(x, y).unwrap();
+            let offset = x as usize * 3;
+            assert_eq!(
+                &row[offset..offset + 3],
+                &[pixel.blue, pixel.green, pixel.red]
+            );
+        }
+        assert_eq!(row[15], 0);
+    }
+}
+
+#[test]
+fn tga_round_trips() {
+    let image = test_image();
+    let bytes = encode(&image, ImageFormat::Tga);
+
+    assert_eq!(bytes[2], 10);
+    assert_eq!(u16::from_le_bytes([bytes[12], bytes[13]]), 5);
+    assert_eq!(u16::from_le_bytes([bytes[14], bytes[15]]), 3);
+    assert_eq!(bytes[16], 24);
+    // The top row is a run of 4 followed by a single raw pixel
+    assert_eq!(bytes[18], 0x80 | 3);
+    assert_eq!(bytes[22], 0);
+
+    let texture = read_tga(bytes.as_slice()).unwrap();
+    assert_eq!(texture.dimensions(), image.dimensions());
+    for y in 0..3 {
+        for x in 0..5 {
+            let texel = texture.get_texel(x, y);
+            assert_eq!(texel.color, image.get_pixel(x, y).unwrap());
+            assert_eq!(texel.alpha, 255);
+        }
+    }
+}
+
+#[test]
+fn tga_rejects_dimensions_over_16_bits() {
+    for dimensions in [Vector2::new(65536, 1), Vector2::new(1, 65536)] {
+        let mut bytes = Vec::new();
+        let error = write_tga(&Image::new(dimensions), &mut bytes).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        // Nothing is written, not even a header with truncated dimensions
+        assert!(bytes.is_empty());
+    }
+
+    let mut bytes = Vec::new();
+    write_tga(&Image::new(Vector2::new(65535, 1)), &mut bytes).unwrap();
+    assert_eq!(u16::from_le_bytes([bytes[12], bytes[13]]), 65535);
+}
+
+// Splits a PNG into its chunks, checking each CRC on the way
+fn png_chunks(bytes: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let crc32 = |data: &[u8]| {
+        let mut crc = u32::MAX;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    0xedb8_8320 ^ (crc >> 1)
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    };
+
+    let mut chunks = Vec::new();
+    let mut offset = 8;
+    while offset < bytes.len() {
+        let length = u32_be(bytes, offset) as usize;
+        let typed_data = &bytes[offset + 4..offset + 8 + length];
+        assert_eq!(u32_be(bytes, offset + 8 + length), crc32(typed_data));
+        chunks.push((typed_data[..4].try_into().unwrap(), &typed_data[4..]));
+        offset += 12 + length;
+    }
+    assert_eq!(offset, bytes.len());
+
+    chunks
+}
+
+#[test]
+fn png_header_and_size() {
+    let image = test_image();
+    let bytes = encode(&image, ImageFormat::Png);
+
+    assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+    let chunks = png_chunks(&bytes);
+    let types: Vec<&[u8; 4]> = chunks.iter().map(|(chunk_type, _)| chunk_type).collect();
+    assert_eq!(types, [b"IHDR", b"IDAT", b"IEND"]);
+
+    let header = chunks[0].1;
+    assert_eq!(u32_be(header, 0), 5);
+    assert_eq!(u32_be(header, 4), 3);
+    assert_eq!(&header[8..], &[8, 2, 0, 0, 0]);
+    assert!(chunks[2].1.is_empty());
+
+    // A zlib header, one final stored block and the Adler-32 of the scanlines
+    let stream = chunks[1].1;
+    let scanline_length = 1 + 5 * 3;
+    assert_eq!(&stream[..2], &[0x78, 0x01]);
+    assert_eq!(u16::from_be_bytes([stream[0], stream[1]]) % 31, 0);
+    assert_eq!(stream[2], 1);
+    let block_length = u16::from_le_bytes([stream[3], stream[4]]) as usize;
+    assert_eq!(block_length, scanline_length * 3);
+    assert_eq!(
+        u16::from_le_bytes([stream[5], stream[6]]),
+        !(block_length as u16)
+    );
+    assert_eq!(stream.len(), 7 + block_length + 4);
+
+    let scanlines = &stream[7..7 + block_length];
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in scanlines {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    assert_eq!(u32_be(stream, 7 + block_length), (b << 16) | a);
+
+    for (y, scanline) in scanlines.chunks(scanline_length).enumerate() {
+        assert_eq!(scanline[0], 0);
+        for (x, rgb) in scanline[1..].chunks(3).enumerate() {
+            let pixel = image.get_pixel(x as u32, y as u32).unwrap();
+            assert_eq!(rgb, &[pixel.red, pixel.green, pixel.blue]);
+        }
+    }
+}
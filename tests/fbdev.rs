@@ -0,0 +1,207 @@
+#![cfg(target_os = "linux")]
+
+use glamour::Vector2;
+use palette::Srgb;
+use std::fs;
+use std::path::PathBuf;
+use sw_render::buffers::image::Image;
+use sw_render::buffers::pixel_format::{PixelFormat, Rgb565, SurfaceFormat, Xrgb8888};
+use sw_render::present::fbdev::{FbdevError, FramebufferDevice, FramebufferLayout};
+use sw_render::present::traits::{PresentMode, PresentTarget, TargetFrameBuffer};
+
+// A plain file of `size` bytes standing in for a device
+fn fake_device(name: &str, size: usize) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}.fb"));
+    fs::write(&path, vec![0xAA; size]).unwrap();
+    path
+}
+
+fn test_image() -> Image {
+    let mut image = Image::new(Vector2::new(2, 2));
+    let mut frame_buffer = image.frame_buffer();
+    frame_buffer.set_pixel(0, 0, Srgb::new(255, 0, 0));
+    frame_buffer.set_pixel(1, 0, Srgb::new(0, 255, 0));
+    frame_buffer.set_pixel(0, 1, Srgb::new(0, 0, 255));
+    frame_buffer.set_pixel(1, 1, Srgb::new(255, 255, 255));
+    image
+}
+
+#[test]
+fn presents_xrgb8888() {
+    let layout = FramebufferLayout::xrgb8888(Vector2::new(2, 2));
+    let path = fake_device("xrgb8888", layout.size());
+    let mut device = FramebufferDevice::open_with_layout(&path, layout).unwrap();
+    assert!(!device.is_double_buffered());
+
+    device.present(&test_image()).unwrap();
+    drop(device);
+
+    let pixels: Vec<u32> = fs::read(&path)
+        .unwrap()
+        .chunks_exact(4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    assert_eq!(pixels, [0xFF0000, 0x00FF00, 0x0000FF, 0xFFFFFF]);
+}
+
+#[test]
+fn presents_rgb565_with_padded_rows() {
+    let mut layout = FramebufferLayout::rgb565(Vector2::new(2, 2));
+    layout.line_length = 6;
+    let path = fake_device("rgb565", layout.size());
+    let mut device = FramebufferDevice::open_with_layout(&path, layout).unwrap();
+
+    device.present(&test_image()).unwrap();
+    assert_eq!(device.front_page(), fs::read(&path).unwrap());
+    drop(device);
+
+    let pixels: Vec<u16> = fs::read(&path)
+        .unwrap()
+        .chunks_exact(2)
+        .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    // The padding is cleared along with the rest of the memory when the device is opened
+    assert_eq!(pixels, [0xF800, 0x07E0, 0, 0x001F, 0xFFFF, 0]);
+}
+
+#[test]
+fn crops_larger_frames() {
+    let layout = FramebufferLayout::xrgb8888(Vector2::new(1, 1));
+    let path = fake_device("crop", layout.size());
+    let mut device = FramebufferDevice::open_with_layout(path, layout).unwrap();
+
+    device.present(&test_image()).unwrap();
+    assert_eq!(device.front_page(), 0xFF0000u32.to_le_bytes());
+}
+
+#[test]
+fn clears_what_smaller_frames_leave_uncovered() {
+    let layout = FramebufferLayout::xrgb8888(Vector2::new(3, 3));
+    let path = fake_device("clear", layout.size());
+    let mut device = FramebufferDevice::open_with_layout(path, layout).unwrap();
+
+    let mut full_frame = Image::new(Vector2::new(3, 3));
+    full_frame
+        .data_mut()
+        .fill(Xrgb8888::pack(Srgb::new(255, 255, 255)));
+    device.present(&full_frame).unwrap();
+    device.present(&test_image()).unwrap();
+
+    let pixels: Vec<u32> = device
+        .front_page()
+        .chunks_exact(4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect();
+    #[rustfmt::skip]
+    assert_eq!(pixels, [
+        0xFF0000, 0x00FF00, 0,
+        0x0000FF, 0xFFFFFF, 0,
+        0, 0, 0,
+    ]);
+}
+
+#[test]
+fn rejects_empty_images() {
+    let layout = FramebufferLayout::xrgb8888(Vector2::new(2, 2));
+    let path = fake_device("empty", layout.size());
+    let mut device = FramebufferDevice::open_with_layout(path, layout).unwrap();
+
+    for dimensions in [Vector2::new(0, 2), Vector2::new(2, 0), Vector2::new(0, 0)] {
+        assert!(matches!(
+            device.present(&Image::new(dimensions)),
+            Err(FbdevError::EmptyImage { width, height })
+                if Vector2::new(width, height) == dimensions
+        ));
+    }
+}
+
+#[test]
+fn rejects_files_smaller_than_the_layout() {
+    let layout = FramebufferLayout::xrgb8888(Vector2::new(4, 4));
+    let path = fake_device("too_small", layout.size() - 1);
+
+    assert!(matches!(
+        FramebufferDevice::open_with_layout(path, layout),
+        Err(FbdevError::TooSmall { .. })
+    ));
+}
+
+#[test]
+fn rejects_lines_shorter_than_a_row() {
+    let mut layout = FramebufferLayout::xrgb8888(Vector2::new(4, 4));
+    layout.line_length = 12;
+    let path = fake_device("short_lines", 64);
+
+    assert!(matches!(
+        FramebufferDevice::open_with_layout(path, layout),
+        Err(FbdevError::LineTooShort {
+            line_length: 12,
+            expected: 16
+        })
+    ));
+}
+
+#[test]
+fn rejects_virtual_heights_below_the_visible_height() {
+    let mut layout = FramebufferLayout::xrgb8888(Vector2::new(4, 4));
+    layout.virtual_height = 3;
+    let path = fake_device("short_virtual_height", 64);
+
+    assert!(matches!(
+        FramebufferDevice::open_with_layout(path, layout),
+        Err(FbdevError::VirtualHeightTooSmall {
+            virtual_height: 3,
+            height: 4
+        })
+    ));
+}
+
+#[test]
+fn rejects_devices_without_framebuffer_info() {
+    let path = fake_device("not_a_device", 16);
+
+    assert!(matches!(
+        FramebufferDevice::open(path),
+        Err(FbdevError::Io(_))
+    ));
+}
+
+#[test]
+fn renders_through_present_target() {
+    let layout = FramebufferLayout::rgb565(Vector2::new(2, 1));
+    let path = fake_device("present_target", layout.size());
+    let mut device = FramebufferDevice::open_with_layout(path, layout).unwrap();
+    let target: &mut dyn PresentTarget<Format = Xrgb8888> = &mut device;
+    assert_eq!(target.surface_format(), SurfaceFormat::Rgb565);
+    assert_eq!(target.present_mode(), PresentMode::Immediate);
+
+    target
+        .present_frame(&mut |frame_buffer| {
+            assert_eq!(frame_buffer.dimensions(), Vector2::new(2, 1));
+            frame_buffer.set_pixel(1, 0, Srgb::new(255, 255, 255));
+        })
+        .unwrap();
+    assert_eq!(device.front_page(), [0, 0, 0xFF, 0xFF]);
+}
+
+fn draw_test_pattern<F: PixelFormat>(frame_buffer: &mut TargetFrameBuffer<'_, '_, F>) {
+    frame_buffer.set_pixel(0, 0, Srgb::new(255, 0, 0));
+    frame_buffer.set_pixel(1, 0, Srgb::new(10, 200, 30));
+    frame_buffer.set_pixel(1, 1, Srgb::new(255, 255, 255));
+}
+
+#[test]
+fn copies_frames_in_the_device_format() {
+    let layout = FramebufferLayout::rgb565(Vector2::new(2, 2));
+    let converted_path = fake_device("converted", layout.size());
+    let mut converted = FramebufferDevice::open_with_layout(converted_path, layout).unwrap();
+    let native_path = fake_device("native", layout.size());
+    let mut native = FramebufferDevice::open_with_layout(native_path, layout)
+        .unwrap()
+        .with_format::<Rgb565>();
+
+    converted.present_frame(&mut draw_test_pattern).unwrap();
+    native.present_frame(&mut draw_test_pattern).unwrap();
+
+    assert_eq!(converted.front_page(), native.front_page());
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+use sw_render::utils::frame_clock::{FrameClock, FrameMode, FrameStats, MAX_DELTA};
+
+const MILLISECOND: Duration = Duration::from_millis(1);
+
+// A clock whose first frame starts well after its creation, so that nothing depends on how long
+// the test took to get there
+fn started_clock(mode: FrameMode) -> (FrameClock, Instant) {
+    let mut clock = FrameClock::new(mode);
+    let start = Instant::now() + Duration::from_secs(1);
+    clock.tick_at(start);
+
+    (clock, start)
+}
+
+#[test]
+fn uncapped_deltas_follow_the_clock() {
+    let (mut clock, start) = started_clock(FrameMode::Uncapped);
+
+    let tick = clock.tick_at(start + 7 * MILLISECOND);
+    assert_eq!(tick.delta, 7 * MILLISECOND);
+    assert_eq!(tick.steps, 1);
+    assert_eq!(clock.deadline(), None);
+}
+
+#[test]
+fn stalls_are_clamped() {
+    let (mut clock, start) = started_clock(FrameMode::Uncapped);
+
+    let tick = clock.tick_at(start + Duration::from_secs(3));
+    assert_eq!(tick.delta, MAX_DELTA);
+    // The statistics still see the real frame time
+    assert_eq!(clock.stats().worst(), Duration::from_secs(3));
+}
+
+#[test]
+fn fixed_timestep_accumulates_steps() {
+    let step = 10 * MILLISECOND;
+    let (mut clock, start) = started_clock(FrameMode::FixedTimestep { step });
+
+    let tick = clock.tick_at(start + 25 * MILLISECOND);
+    assert_eq!(tick.delta, step);
+    assert_eq!(tick.steps, 2);
+    assert!((tick.alpha - 0.5).abs() < 1e-4);
+
+    // The leftover 5 ms carry over into the next frame
+    let tick = clock.tick_at(start + 30 * MILLISECOND);
+    assert_eq!(tick.steps, 1);
+    assert!(tick.alpha.abs() < 1e-4);
+
+    let tick = clock.tick_at(start + 33 * MILLISECOND);
+    assert_eq!(tick.steps, 0);
+    assert!((tick.alpha - 0.3).abs() < 1e-4);
+}
+
+#[test]
+fn capped_frames_keep_their_cadence() {
+    let (mut clock, start) = started_clock(FrameMode::Capped { fps: 50 });
+    let period = 20 * MILLISECOND;
+    assert_eq!(clock.deadline(), Some(start + period));
+
+    // Waking up a little late does not push the following frames back
+    clock.tick_at(start + period + 2 * MILLISECOND);
+    assert_eq!(clock.deadline(), Some(start + 2 * period));
+
+    // Missed frames are dropped rather than rendered back to back
+    let late = start + 10 * period;
+    clock.tick_at(late);
+    assert_eq!(clock.deadline(), Some(late + period));
+}
+
+#[test]
+fn stats_summarize_the_window() {
+    let mut stats = FrameStats::new(200);
+    assert_eq!(stats.average(), Duration::ZERO);
+    assert_eq!(stats.fps(), 0.0);
+
+    for _ in 0..198 {
+        stats.record(10 * MILLISECOND);
+    }
+    stats.record(30 * MILLISECOND);
+    stats.record(50 * MILLISECOND);
+
+    assert_eq!(stats.len(), 200);
+    assert_eq!(stats.worst(), 50 * MILLISECOND);
+    // The slowest 1% of 200 frames are the two spikes
+    assert_eq!(stats.one_percent_low(), 40 * MILLISECOND);
+    assert_eq!(stats.average(), Duration::from_micros(10_300));
+
+    // Older frames fall out of the window
+    for _ in 0..200 {
+        stats.record(20 * MILLISECOND);
+    }
+    assert_eq!(stats.len(), 200);
+    assert_eq!(stats.worst(), 20 * MILLISECOND);
+    assert!((stats.fps() - 50.0).abs() < 1e-3);
+}
@@ -0,0 +1,231 @@
+use glam::Quat;
+use glamour::Vector2;
+use palette::LinSrgb;
+use std::io::Cursor;
+use std::rc::Rc;
+use sw_render::buffers::depth::DepthBuffer;
+use sw_render::buffers::image::Image;
+use sw_render::common::camera::{Camera, OrthographicCamera, PerspectiveCamera};
+use sw_render::common::frustum::{BoundingSphere, Frustum};
+use sw_render::common::space::{WorldBox, WorldPoint, WorldVector};
+use sw_render::common::traits::Bounded;
+use sw_render::lights::directional_light::DirectionalLight;
+use sw_render::lights::shadow_map::ShadowMap;
+use sw_render::lights::traits::LightSource;
+use sw_render::objects::mesh::Mesh;
+use sw_render::objects::object::Object;
+use sw_render::pipeline::renderer::Renderer;
+use sw_render::shaders::shading::{Material, ShadingModel, SpecularModel};
+
+// A unit square facing +Z, centered on the origin
+const QUAD_OBJ: &str = "\
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+f 1 2 3 4
+";
+
+// A unit cube centered on the origin
+const CUBE_OBJ: &str = "\
+v -0.5 -0.5 -0.5
+v 0.5 -0.5 -0.5
+v 0.5 0.5 -0.5
+v -0.5 0.5 -0.5
+v -0.5 -0.5 0.5
+v 0.5 -0.5 0.5
+v 0.5 0.5 0.5
+v -0.5 0.5 0.5
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 4 8 7 3
+f 1 5 8 4
+f 2 3 7 6
+";
+
+// At (0, 0, 5) looking down -Z, 90° vertically and square, seeing 1 to 10 units ahead
+fn perspective_camera() -> PerspectiveCamera {
+    PerspectiveCamera::new(
+        WorldPoint::new(0.0, 0.0, 5.0),
+        WorldVector::new(0.0, 0.0, -1.0),
+        1.0,
+        10.0,
+        90.0,
+        1.0,
+    )
+}
+
+fn unit_box(center: WorldPoint) -> WorldBox {
+    WorldBox::new(
+        center - WorldVector::splat(0.5),
+        center + WorldVector::splat(0.5),
+    )
+}
+
+#[test]
+fn planes_face_inwards() {
+    let frustum = perspective_camera().frustum();
+    for plane in frustum.planes() {
+        assert!((plane.normal.length() - 1.0).abs() < 1e-4);
+        assert!(plane.signed_distance(WorldPoint::ZERO) > 0.0);
+    }
+
+    assert!(frustum.contains_point(WorldPoint::ZERO));
+    assert!(frustum.contains_point(WorldPoint::new(2.9, 2.9, 2.0)));
+    // Behind the camera, closer than the near plane, past the far plane
+    assert!(!frustum.contains_point(WorldPoint::new(0.0, 0.0, 6.0)));
+    assert!(!frustum.contains_point(WorldPoint::new(0.0, 0.0, 4.5)));
+    assert!(!frustum.contains_point(WorldPoint::new(0.0, 0.0, -5.5)));
+    // Outside the 90° cone, which is 5 units wide on either side at the origin
+    assert!(!frustum.contains_point(WorldPoint::new(5.5, 0.0, 0.0)));
+    assert!(!frustum.contains_point(WorldPoint::new(0.0, -5.5, 0.0)));
+}
+
+#[test]
+fn boxes_and_spheres_are_culled_only_when_fully_outside() {
+    let frustum = perspective_camera().frustum();
+
+    assert!(frustum.intersects_box(&unit_box(WorldPoint::ZERO)));
+    // Straddling the right plane
+    assert!(frustum.intersects_box(&unit_box(WorldPoint::new(5.2, 0.0, 0.0))));
+    assert!(!frustum.intersects_box(&unit_box(WorldPoint::new(7.0, 0.0, 0.0))));
+    assert!(!frustum.intersects_box(&unit_box(WorldPoint::new(0.0, 0.0, 7.0))));
+    assert!(!frustum.intersects_box(&unit_box(WorldPoint::new(0.0, 0.0, -6.0))));
+
+    let sphere = |center, radius| BoundingSphere { center, radius };
+    assert!(frustum.intersects_sphere(&sphere(WorldPoint::ZERO, 0.1)));
+    assert!(frustum.intersects_sphere(&sphere(WorldPoint::new(0.0, 0.0, 6.0), 2.0)));
+    assert!(!frustum.intersects_sphere(&sphere(WorldPoint::new(0.0, 0.0, 6.0), 0.5)));
+    assert!(!frustum.intersects_sphere(&sphere(WorldPoint::new(0.0, 20.0, 0.0), 1.0)));
+}
+
+#[test]
+fn orthographic_frusta_are_boxes() {
+    let camera = OrthographicCamera::new(
+        WorldPoint::new(0.0, 0.0, 5.0),
+        WorldVector::new(0.0, 0.0, -1.0),
+        1.0,
+        10.0,
+        2.0,
+        2.0,
+    );
+    let frustum = camera.frustum();
+
+    // 4 wide and 2 high whatever the distance
+    assert!(frustum.contains_point(WorldPoint::new(1.9, 0.9, 3.9)));
+    assert!(frustum.contains_point(WorldPoint::new(1.9, 0.9, -4.9)));
+    assert!(!frustum.contains_point(WorldPoint::new(2.1, 0.0, -4.0)));
+    assert!(!frustum.contains_point(WorldPoint::new(0.0, 1.1, 3.0)));
+}
+
+#[test]
+fn bounded_objects_get_an_enclosing_sphere() {
+    let mut quad = Object::new(Rc::new(Mesh::from_obj(Cursor::new(QUAD_OBJ)).unwrap()));
+    quad.set_translation(WorldVector::new(0.0, 0.0, -2.0));
+    quad.update_world_transforms();
+
+    let sphere = quad.calculate_bounding_sphere();
+    assert_eq!(sphere.center, WorldPoint::new(0.0, 0.0, -2.0));
+    assert!((sphere.radius - 0.5f32.sqrt()).abs() < 1e-4);
+    assert!(perspective_camera().frustum().intersects(&quad));
+}
+
+// Three quads: one in view, one behind the camera and one far off to the side
+fn scene() -> Object {
+    let mesh = Rc::new(Mesh::from_obj(Cursor::new(QUAD_OBJ)).unwrap());
+    let mut root = Object::empty();
+    for translation in [
+        WorldVector::new(0.0, 0.0, 0.0),
+        WorldVector::new(0.0, 0.0, 8.0),
+        WorldVector::new(-50.0, 0.0, 0.0),
+    ] {
+        root.add_child(Object::new(mesh.clone()))
+            .set_translation(translation);
+    }
+    root.update_world_transforms();
+
+    root
+}
+
+fn render(renderer: &Renderer, scene: &Object) -> (Image, usize) {
+    let dimensions = Vector2::new(32, 32);
+    let light = DirectionalLight::new(
+        WorldVector::new(0.0, 0.0, -1.0),
+        LinSrgb::new(1.0, 1.0, 1.0),
+        1.0,
+    );
+    let lights: [&dyn LightSource; 1] = [&light];
+    let mut image = Image::new(dimensions);
+    let mut depth_buffer = DepthBuffer::new(dimensions);
+    let mut frame_buffer = image.frame_buffer();
+    let mut render_pass = renderer.begin_pass(
+        &mut frame_buffer,
+        &mut depth_buffer,
+        &perspective_camera(),
+        &lights,
+    );
+    render_pass.draw_shaded(
+        scene,
+        ShadingModel::Phong(SpecularModel::BlinnPhong),
+        &Material::default(),
+    );
+    let culled_meshes = render_pass.culled_meshes();
+
+    (image, culled_meshes)
+}
+
+#[test]
+fn renderer_skips_meshes_outside_the_view() {
+    let scene = scene();
+    let mut renderer = Renderer::new();
+    let (culled_image, culled_meshes) = render(&renderer, &scene);
+    assert_eq!(culled_meshes, 2);
+
+    renderer.set_frustum_culling(false);
+    let (image, culled_meshes) = render(&renderer, &scene);
+    assert_eq!(culled_meshes, 0);
+
+    assert_eq!(culled_image.data(), image.data());
+    assert!(image.data().iter().any(|&pixel| pixel != 0));
+}
+
+#[test]
+fn rotated_meshes_are_culled_by_their_sphere() {
+    // Turned by 45°, the cube's world box grows enough to straddle the right plane while the cube
+    // itself stays outside
+    let mut cube = Object::new(Rc::new(Mesh::from_obj(Cursor::new(CUBE_OBJ)).unwrap()));
+    cube.set_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_4));
+    cube.set_translation(WorldVector::new(6.3, 0.0, 0.0));
+    cube.update_world_transforms();
+
+    let frustum = perspective_camera().frustum();
+    assert!(frustum.intersects_box(&cube.calculate_bounding_box()));
+
+    let (image, culled_meshes) = render(&Renderer::new(), &cube);
+    assert_eq!(culled_meshes, 1);
+    assert!(image.data().iter().all(|&pixel| pixel == 0));
+}
+
+#[test]
+fn shadow_passes_skip_casters_outside_the_light() {
+    let scene = scene();
+    let renderer = Renderer::new();
+    let mut shadow_map = ShadowMap::new(Vector2::new(32, 32));
+    let light_camera = OrthographicCamera::new(
+        WorldPoint::new(0.0, 0.0, 20.0),
+        WorldVector::new(0.0, 0.0, -1.0),
+        0.0,
+        40.0,
+        4.0,
+        1.0,
+    );
+
+    let mut shadow_pass = renderer.begin_shadow_pass_from_camera(&mut shadow_map, &light_camera);
+    shadow_pass.draw_object(&scene);
+    assert_eq!(shadow_pass.culled_meshes(), 1);
+    assert_eq!(
+        Frustum::from_world_to_clip(&shadow_map.world_to_clip()),
+        light_camera.frustum()
+    );
+}
@@ -0,0 +1,353 @@
+use glamour::{Angle, Vector2};
+use palette::{LinSrgb, Srgb};
+use std::env;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::rc::Rc;
+use sw_render::buffers::depth::DepthBuffer;
+use sw_render::buffers::image::Image;
+use sw_render::common::camera::{Camera, OrthographicCamera, PerspectiveCamera};
+use sw_render::common::primitives::PolygonPoints2;
+use sw_render::common::space::{ScreenPoint, ScreenVector, WorldPoint, WorldVector};
+use sw_render::lights::directional_light::DirectionalLight;
+use sw_render::lights::traits::LightSource;
+use sw_render::objects::mesh::Mesh;
+use sw_render::objects::object::Object;
+use sw_render::pipeline::renderer::Renderer;
+use sw_render::shaders::shading::{Material, ShadingModel, SpecularModel};
+use sw_render::textures::tga::read_tga;
+
+#[derive(Clone, Copy)]
+struct Tolerance {
+    // Largest per-channel difference that still counts as a match
+    channel: u8,
+    // Share of pixels allowed to exceed the channel tolerance
+    pixels: f32,
+}
+
+impl Tolerance {
+    // Absorbs floating-point noise between platforms in shaded images
+    const SHADED: Self = Self {
+        channel: 2,
+        pixels: 0.001,
+    };
+    // Rasterization is fixed-point, a single differing pixel is a fill rule change
+    const EXACT: Self = Self {
+        channel: 0,
+        pixels: 0.0,
+    };
+}
+
+// Set to regenerate the golden images after an intended change in output
+const UPDATE_ENV_VAR: &str = "UPDATE_GOLDEN";
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.tga"))
+}
+
+fn failure_path(name: &str, kind: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("golden")
+        .join(format!("{name}.{kind}.png"))
+}
+
+// Compares `actual` against the checked-in golden image. On a mismatch the actual image and a
+// diff, with failing pixels in red over a dimmed copy of the golden one, are written next to
+// the build's temporary files.
+fn assert_golden(name: &str, actual: &Image, tolerance: Tolerance) {
+    let golden_path = golden_path(name);
+    if env::var_os(UPDATE_ENV_VAR).is_some() {
+        fs::create_dir_all(golden_path.parent().unwrap()).unwrap();
+        actual.save(&golden_path).unwrap();
+        return;
+    }
+
+    let golden_file = File::open(&golden_path).unwrap_or_else(|error| {
+        panic!(
+            "missing golden image {}: {error}, run with {UPDATE_ENV_VAR}=1 to create it",
+            golden_path.display()
+        )
+    });
+    let golden = read_tga(BufReader::new(golden_file)).unwrap();
+    assert_eq!(
+        golden.dimensions(),
+        actual.dimensions(),
+        "golden image {name} has different dimensions"
+    );
+
+    let dimensions = actual.dimensions();
+    let mut diff = Image::new(dimensions);
+    let mut mismatches = 0;
+    {
+        let mut diff_buffer = diff.frame_buffer();
+        for y in 0..dimensions.y {
+            for x in 0..dimensions.x {
+                let expected = golden.get_texel(x, y).color;
+                let pixel = actual.get_pixel(x, y).unwrap();
+                let difference = [
+                    expected.red.abs_diff(pixel.red),
+                    expected.green.abs_diff(pixel.green),
+                    expected.blue.abs_diff(pixel.blue),
+                ];
+
+                if difference
+                    .iter()
+                    .any(|&channel| channel > tolerance.channel)
+                {
+                    mismatches += 1;
+                    diff_buffer.set_pixel(x, y, Srgb::new(255, 0, 0));
+                } else {
+                    let luma =
+                        ((expected.red as u32 + expected.green as u32 + expected.blue as u32) / 9)
+                            as u8;
+                    diff_buffer.set_pixel(x, y, Srgb::new(luma, luma, luma));
+                }
+            }
+        }
+    }
+
+    let pixel_count = (dimensions.x * dimensions.y) as f32;
+    if mismatches as f32 > pixel_count * tolerance.pixels {
+        let actual_path = failure_path(name, "actual");
+        let diff_path = failure_path(name, "diff");
+        fs::create_dir_all(actual_path.parent().unwrap()).unwrap();
+        actual.save(&actual_path).unwrap();
+        diff.save(&diff_path).unwrap();
+
+        panic!(
+            "{name}: {mismatches} of {pixel_count} pixels differ from the golden image, see {} and {}",
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+fn load_head() -> Object {
+    let mesh_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("african_head.obj");
+    let mesh = Mesh::from_obj(BufReader::new(File::open(mesh_path).unwrap())).unwrap();
+    let mut head = Object::new(Rc::new(mesh));
+    head.update_world_transforms();
+
+    head
+}
+
+fn render_head(camera_position: WorldPoint, shading_model: ShadingModel) -> Image {
+    let head = load_head();
+    let mut camera = PerspectiveCamera::new(
+        camera_position,
+        WorldVector::new(0.0, 0.0, -1.0),
+        0.1,
+        10.0,
+        37.5,
+        1.0,
+    );
+    camera.look_at(&head);
+
+    render_scene(&head, &camera, shading_model)
+}
+
+fn render_scene(head: &Object, camera: &dyn Camera, shading_model: ShadingModel) -> Image {
+    let dimensions = Vector2::new(128, 128);
+    let light = DirectionalLight::new(
+        WorldVector::new(-1.0, -1.0, -1.0),
+        LinSrgb::new(1.0, 1.0, 1.0),
+        1.0,
+    );
+    let lights: [&dyn LightSource; 1] = [&light];
+
+    let renderer = Renderer::new();
+    let mut image = Image::new(dimensions);
+    let mut depth_buffer = DepthBuffer::new(dimensions);
+    {
+        let mut frame_buffer = image.frame_buffer();
+        let mut render_pass =
+            renderer.begin_pass(&mut frame_buffer, &mut depth_buffer, camera, &lights);
+        render_pass.draw_shaded(head, shading_model, &Material::default());
+    }
+
+    image
+}
+
+fn orbit_position(degrees: f32, height: f32) -> WorldPoint {
+    let (sin, cos) = Angle::from_degrees(degrees).sin_cos();
+    WorldPoint::new(3.5 * sin, height, 3.5 * cos)
+}
+
+#[test]
+fn head_front() {
+    let image = render_head(
+        orbit_position(0.0, 0.0),
+        ShadingModel::Phong(SpecularModel::BlinnPhong),
+    );
+    assert_golden("head_front", &image, Tolerance::SHADED);
+}
+
+#[test]
+fn head_side() {
+    let image = render_head(
+        orbit_position(90.0, 0.0),
+        ShadingModel::Phong(SpecularModel::BlinnPhong),
+    );
+    assert_golden("head_side", &image, Tolerance::SHADED);
+}
+
+#[test]
+fn head_above_behind() {
+    let image = render_head(
+        orbit_position(225.0, 2.0),
+        ShadingModel::Phong(SpecularModel::Phong),
+    );
+    assert_golden("head_above_behind", &image, Tolerance::SHADED);
+}
+
+#[test]
+fn head_gouraud() {
+    let image = render_head(
+        orbit_position(-30.0, 0.5),
+        ShadingModel::Gouraud(SpecularModel::BlinnPhong),
+    );
+    assert_golden("head_gouraud", &image, Tolerance::SHADED);
+}
+
+// Triangles sharing edges are drawn additively, so any pixel claimed twice or not at all by the
+// fill rule shows up in the golden image. Each group tiles a region, every pixel whose center is
+// inside a region has to be claimed by exactly one of its triangles.
+fn render_triangles(groups: &[&[[(f32, f32); 3]]]) -> Image {
+    let dimensions = Vector2::new(32, 32);
+    let mut coverage = vec![0u8; (dimensions.x * dimensions.y) as usize];
+    let mut triangle_idx = 0;
+    for group in groups {
+        let polygons: Vec<PolygonPoints2> = group
+            .iter()
+            .map(|triangle| PolygonPoints2::new(triangle.map(|(x, y)| ScreenPoint::new(x, y))))
+            .collect();
+
+        let mut group_bits = 0u8;
+        for polygon in &polygons {
+            let bit = 1 << (triangle_idx % 8);
+            group_bits |= bit;
+            triangle_idx += 1;
+            polygon.rasterize(dimensions.x, dimensions.y, |x, y, _| {
+                coverage[(y * dimensions.x + x) as usize] += bit;
+            });
+        }
+
+        // Centers on the region's outline are left to the fill rule, so only centers with some
+        // room around them are checked
+        let is_inside = |point: ScreenPoint| {
+            polygons
+                .iter()
+                .any(|polygon| polygon.barycentric(point).is_some())
+        };
+        for y in 0..dimensions.y {
+            for x in 0..dimensions.x {
+                let center = ScreenPoint::new(x as f32 + 0.5, y as f32 + 0.5);
+                let is_interior = [(-0.01, -0.01), (0.01, -0.01), (-0.01, 0.01), (0.01, 0.01)]
+                    .iter()
+                    .all(|&(dx, dy)| is_inside(center + ScreenVector::new(dx, dy)));
+                let bits = coverage[(y * dimensions.x + x) as usize] & group_bits;
+                assert!(
+                    !is_interior || bits.count_ones() == 1,
+                    "pixel ({x}, {y}) is covered by {bits:#010b}"
+                );
+            }
+        }
+    }
+
+    let mut image = Image::new(dimensions);
+    let mut frame_buffer = image.frame_buffer();
+    for y in 0..dimensions.y {
+        for x in 0..dimensions.x {
+            let value = coverage[(y * dimensions.x + x) as usize];
+            frame_buffer.set_pixel(
+                x,
+                y,
+                Srgb::new(value.wrapping_mul(37), value.wrapping_mul(91), value),
+            );
+        }
+    }
+
+    image
+}
+
+#[test]
+fn fill_rule_shared_edges() {
+    // A quad split along both diagonals, with vertices on pixel centers and pixel corners
+    let image = render_triangles(&[
+        &[
+            [(4.5, 4.5), (27.5, 4.5), (27.5, 27.5)],
+            [(4.5, 4.5), (27.5, 27.5), (4.5, 27.5)],
+        ],
+        &[
+            [(2.0, 2.0), (14.0, 2.0), (2.0, 14.0)],
+            [(14.0, 2.0), (14.0, 14.0), (2.0, 14.0)],
+        ],
+    ]);
+    assert_golden("fill_rule_shared_edges", &image, Tolerance::EXACT);
+}
+
+#[test]
+fn fill_rule_fan() {
+    // Both windings around a shared center, including horizontal and vertical edges
+    let center = (16.5, 16.5);
+    let rim = [
+        (16.5, 2.5),
+        (28.0, 6.0),
+        (30.5, 16.5),
+        (26.0, 27.0),
+        (16.5, 30.5),
+        (5.0, 25.0),
+        (2.5, 16.5),
+        (7.0, 5.0),
+    ];
+    let triangles: Vec<[(f32, f32); 3]> = (0..rim.len())
+        .map(|idx| {
+            let (from, to) = (rim[idx], rim[(idx + 1) % rim.len()]);
+            if idx % 2 == 0 {
+                [center, from, to]
+            } else {
+                [to, from, center]
+            }
+        })
+        .collect();
+
+    let image = render_triangles(&[&triangles]);
+    assert_golden("fill_rule_fan", &image, Tolerance::EXACT);
+}
+
+#[test]
+fn fill_rule_slivers() {
+    // Thin and subpixel triangles that may legitimately cover no pixel at all
+    let image = render_triangles(&[&[
+        [(1.0, 1.0), (31.0, 2.0), (1.0, 1.6)],
+        [(3.5, 5.0), (3.6, 30.0), (3.5, 30.0)],
+        [(10.2, 10.2), (10.8, 10.3), (10.4, 10.9)],
+        [(20.5, 20.5), (21.5, 20.5), (20.5, 21.5)],
+        [(8.0, 28.0), (30.0, 8.0), (30.0, 8.5)],
+    ]]);
+    assert_golden("fill_rule_slivers", &image, Tolerance::EXACT);
+}
+
+#[test]
+fn head_orthographic() {
+    let head = load_head();
+    let mut camera = OrthographicCamera::new(
+        WorldPoint::new(3.0, 2.5, 3.0),
+        WorldVector::new(0.0, 0.0, -1.0),
+        0.1,
+        10.0,
+        2.5,
+        1.0,
+    );
+    camera.look_at(&head);
+
+    let image = render_scene(
+        &head,
+        &camera,
+        ShadingModel::Phong(SpecularModel::BlinnPhong),
+    );
+    assert_golden("head_orthographic", &image, Tolerance::SHADED);
+}
@@ -0,0 +1,159 @@
+use glamour::Angle;
+use palette::LinSrgb;
+use sw_render::common::space::{ModelToClipTransform, ModelToWorldTransform, TexturePoint};
+use sw_render::common::space::{WorldPoint, WorldVector};
+use sw_render::lights::directional_light::DirectionalLight;
+use sw_render::lights::positional_light::PositionalLight;
+use sw_render::lights::spotlight::Spotlight;
+use sw_render::lights::traits::{Attenuation, LightSource};
+use sw_render::shaders::shading::{Material, PhongShading};
+use sw_render::shaders::traits::{SceneUniforms, Shader};
+
+const WHITE: LinSrgb = LinSrgb::new(1.0, 1.0, 1.0);
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "expected {expected}, got {actual}"
+    );
+}
+
+// Light arriving at a white, purely diffuse surface, as shaded by `PhongShading`
+fn irradiance(lights: &[&dyn LightSource], point: WorldPoint, normal: WorldVector) -> f32 {
+    let material = Material {
+        ambient: LinSrgb::new(0.0, 0.0, 0.0),
+        diffuse: WHITE,
+        specular: LinSrgb::new(0.0, 0.0, 0.0),
+        ..Default::default()
+    };
+    let scene = SceneUniforms {
+        model_to_world: ModelToWorldTransform::IDENTITY,
+        normal_to_world: ModelToWorldTransform::IDENTITY,
+        model_to_clip: ModelToClipTransform::IDENTITY,
+        camera_position: WorldPoint::new(0.0, 10.0, 10.0),
+        lights,
+    };
+
+    PhongShading::default()
+        .fragment(&(point, normal, TexturePoint::ZERO), &material, &scene)
+        .unwrap()
+        .red
+}
+
+// Two units above the origin, pointing straight down, without any falloff over distance
+fn spotlight() -> Spotlight {
+    Spotlight::new(
+        WorldPoint::new(0.0, 2.0, 0.0),
+        WorldVector::new(0.0, -1.0, 0.0),
+        WHITE,
+        1.0,
+        Attenuation::none(),
+        Angle::from_degrees(20.0),
+        Angle::from_degrees(30.0),
+    )
+}
+
+// The point on the ground seen from the spotlight at `degrees` off its axis
+fn ground_point(degrees: f32) -> WorldPoint {
+    WorldPoint::new(2.0 * degrees.to_radians().tan(), 0.0, 0.0)
+}
+
+#[test]
+fn spotlights_fade_out_between_their_cones() {
+    let spotlight = spotlight();
+    let lights: [&dyn LightSource; 1] = [&spotlight];
+    let irradiance_at = |degrees: f32| irradiance(&lights, ground_point(degrees), WorldVector::Y);
+
+    // Full intensity inside the inner cone, only Lambert's cosine law applies
+    assert_close(irradiance_at(0.0), 1.0);
+    assert_close(irradiance_at(10.0), 10f32.to_radians().cos());
+    assert_close(irradiance_at(19.9), 19.9f32.to_radians().cos());
+
+    // Smoothstep on the cosine between the cones
+    let (cos_inner, cos_outer) = (20f32.to_radians().cos(), 30f32.to_radians().cos());
+    let t = (25f32.to_radians().cos() - cos_outer) / (cos_inner - cos_outer);
+    let cone_factor = t * t * (3.0 - 2.0 * t);
+    assert!(0.0 < cone_factor && cone_factor < 1.0);
+    assert_close(irradiance_at(25.0), cone_factor * 25f32.to_radians().cos());
+
+    // Nothing from the edge of the outer cone on
+    assert_close(irradiance_at(30.0), 0.0);
+    assert_eq!(irradiance_at(45.0), 0.0);
+    assert_eq!(irradiance_at(-60.0), 0.0);
+}
+
+#[test]
+fn lights_are_attenuated_with_distance() {
+    for (attenuation, expected) in [
+        // Inverse-square by default
+        (Attenuation::default(), [1.0 / 2.0, 1.0 / 5.0, 1.0 / 17.0]),
+        (Attenuation::none(), [1.0; 3]),
+        // 1 / (1 + d / 2 + d² / 4)
+        (
+            Attenuation::new(1.0, 0.5, 0.25),
+            [1.0 / 1.75, 1.0 / 3.0, 1.0 / 7.0],
+        ),
+    ] {
+        let light = PositionalLight::new(WorldPoint::ZERO, WHITE, 1.0, attenuation);
+        let lights: [&dyn LightSource; 1] = [&light];
+        for (distance, expected) in [1.0, 2.0, 4.0].into_iter().zip(expected) {
+            assert_close(attenuation.factor(distance), expected);
+            // Facing the light
+            assert_close(
+                irradiance(
+                    &lights,
+                    WorldPoint::new(distance, 0.0, 0.0),
+                    -WorldVector::X,
+                ),
+                expected,
+            );
+        }
+    }
+
+    // Denominators that are not positive give no light instead of an infinite or negative one
+    assert_eq!(Attenuation::new(0.0, 0.0, 0.0).factor(1.0), 0.0);
+    assert_eq!(Attenuation::new(1.0, -1.0, 0.0).factor(2.0), 0.0);
+
+    // Spotlights attenuate the same way on top of their cone
+    let mut spotlight = spotlight();
+    spotlight.attenuation = Attenuation::default();
+    let lights: [&dyn LightSource; 1] = [&spotlight];
+    assert_close(
+        irradiance(&lights, WorldPoint::ZERO, WorldVector::Y),
+        1.0 / 5.0,
+    );
+}
+
+#[test]
+fn lights_add_up() {
+    let point = WorldPoint::new(0.5, 0.0, 0.0);
+    let normal = WorldVector::Y;
+    let sun = DirectionalLight::new(WorldVector::new(-1.0, -1.0, 0.0), WHITE, 0.5);
+    let lamp = PositionalLight::new(
+        WorldPoint::new(0.5, 3.0, 0.0),
+        WHITE,
+        2.0,
+        Attenuation::default(),
+    );
+    let spotlight = spotlight();
+    // Below the surface, so it adds nothing
+    let under_lamp = PositionalLight::new(
+        WorldPoint::new(0.5, -1.0, 0.0),
+        WHITE,
+        5.0,
+        Attenuation::none(),
+    );
+
+    let lights: [&dyn LightSource; 4] = [&sun, &lamp, &spotlight, &under_lamp];
+    let separately: f32 = lights
+        .iter()
+        .map(|&light| irradiance(&[light], point, normal))
+        .sum();
+    assert_close(irradiance(&[&under_lamp], point, normal), 0.0);
+    assert_close(irradiance(&lights, point, normal), separately);
+
+    // Each one on its own, in order: 45° off the normal, straight above 3 units away, and
+    // inside the spotlight's inner cone
+    let expected = 0.5 * std::f32::consts::FRAC_1_SQRT_2 + 2.0 / 10.0 + 2.0 / 0.5f32.hypot(2.0);
+    assert_close(separately, expected);
+}
@@ -0,0 +1,48 @@
+use obj::{LoadErrorKind, ObjError};
+use std::io::Cursor;
+use sw_render::common::space::{ModelVector, TexturePoint};
+use sw_render::objects::mesh::Mesh;
+
+const TRIANGLE_VERTICES: &str = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vt 1 1
+vn 0 0 2
+";
+
+fn load(faces: &str) -> Result<Mesh, ObjError> {
+    Mesh::from_obj(Cursor::new(format!("{TRIANGLE_VERTICES}{faces}")))
+}
+
+#[test]
+fn loads_positions_texture_coordinates_and_normals() {
+    let mesh = load("f 1/1/1 2/2/1 3/1/1\n").unwrap();
+
+    assert_eq!(mesh.vertices.len(), 3);
+    assert_eq!(mesh.tris_face_indices(), [[0, 1, 2]]);
+    assert_eq!(
+        mesh.vertices[1].texture_coordinates,
+        TexturePoint::new(1.0, 1.0)
+    );
+    assert_eq!(mesh.vertices[2].normal, ModelVector::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn out_of_range_indices_are_errors() {
+    for faces in [
+        "f 1 2 4\n",
+        "f 1 2 -4\n",
+        "f 0 1 2\n",
+        "f 1/1 2/3 3/1\n",
+        "f 1//1 2//2 3//1\n",
+        "f 1/1/1 2/1/1 3/9/1\n",
+    ] {
+        let error = load(faces).err();
+        assert!(
+            matches!(&error, Some(ObjError::Load(error)) if *error.kind() == LoadErrorKind::IndexOutOfRange),
+            "{faces:?} gave {error:?}"
+        );
+    }
+}
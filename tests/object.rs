@@ -0,0 +1,112 @@
+use glam::Quat;
+use glamour::{Angle, Vector2};
+use std::io::Cursor;
+use std::rc::Rc;
+use sw_render::common::camera::OrthographicCamera;
+use sw_render::common::space::{ModelPoint, WorldPoint, WorldVector};
+use sw_render::common::traits::Positionable;
+use sw_render::lights::shadow_map::ShadowMap;
+use sw_render::objects::mesh::Mesh;
+use sw_render::objects::object::Object;
+use sw_render::pipeline::renderer::Renderer;
+
+const TRIANGLE_OBJ: &str = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+f 1 2 3
+";
+
+fn triangle() -> Object {
+    Object::new(Rc::new(Mesh::from_obj(Cursor::new(TRIANGLE_OBJ)).unwrap()))
+}
+
+fn assert_point_close(actual: WorldPoint, expected: WorldPoint) {
+    assert!(
+        (actual - expected).length() < 1e-4,
+        "expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[test]
+fn children_inherit_their_parents_transform() {
+    let mut root = Object::empty();
+    root.set_translation(WorldVector::new(0.0, 0.0, -5.0));
+    root.rotate(WorldVector::Y, Angle::from_degrees(90.0));
+    root.set_scale(WorldVector::splat(2.0));
+    root.add_child(triangle())
+        .set_translation(WorldVector::new(1.0, 0.0, 0.0));
+    root.update_world_transforms();
+
+    // One unit along the parent's X is two along the world's -Z after the quarter turn
+    let child = &root.children()[0];
+    assert_point_close(child.get_position(), WorldPoint::new(0.0, 0.0, -7.0));
+    assert_point_close(
+        child
+            .world_transform()
+            .map_point(ModelPoint::new(0.0, 1.0, 0.0)),
+        WorldPoint::new(0.0, 2.0, -7.0),
+    );
+}
+
+#[test]
+fn moving_a_node_flags_the_hierarchy_until_it_is_updated() {
+    let mut root = Object::empty();
+    root.add_child(triangle());
+    assert!(root.needs_world_transform_update());
+    root.update_world_transforms();
+    assert!(!root.needs_world_transform_update());
+
+    root.children_mut()[0].set_rotation(Quat::from_rotation_z(1.0));
+    assert!(root.needs_world_transform_update());
+    root.update_world_transforms();
+    assert!(!root.needs_world_transform_update());
+}
+
+#[test]
+fn moved_objects_keep_their_last_world_transform_until_updated() {
+    let mut object = triangle();
+    object.update_world_transforms();
+    object.set_translation(WorldVector::new(1.0, 0.0, 0.0));
+
+    // Looking at a moved object is fine, it is only seen where it moves after the update
+    let mut camera = OrthographicCamera::new(
+        WorldPoint::new(0.0, 0.0, 5.0),
+        WorldVector::new(1.0, 0.0, 0.0),
+        0.1,
+        10.0,
+        4.0,
+        1.0,
+    );
+    camera.look_at(&object);
+    assert_point_close(object.get_position(), WorldPoint::ZERO);
+    // Towards the center of the triangle's bounds where it was
+    let forward = camera.pose().forward();
+    assert!((forward - WorldVector::new(0.5, 0.5, -5.0).normalize()).length() < 1e-4);
+
+    object.update_world_transforms();
+    assert_point_close(object.get_position(), WorldPoint::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "update_world_transforms")]
+fn drawing_a_moved_object_without_an_update_panics_in_debug_builds() {
+    let mut object = triangle();
+    object.update_world_transforms();
+    object.set_translation(WorldVector::new(1.0, 0.0, 0.0));
+
+    let renderer = Renderer::new();
+    let mut shadow_map = ShadowMap::new(Vector2::new(8, 8));
+    let camera = OrthographicCamera::new(
+        WorldPoint::new(0.0, 0.0, 5.0),
+        WorldVector::new(0.0, 0.0, -1.0),
+        0.1,
+        10.0,
+        4.0,
+        1.0,
+    );
+    renderer
+        .begin_shadow_pass_from_camera(&mut shadow_map, &camera)
+        .draw_object(&object);
+}
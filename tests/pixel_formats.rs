@@ -0,0 +1,74 @@
+use glamour::Vector2;
+use palette::Srgb;
+use sw_render::buffers::frame::FrameBuffer;
+use sw_render::buffers::image::Image;
+use sw_render::buffers::pixel_format::{PixelFormat, Rgb565, Rgba8888, Xrgb8888, L8};
+use sw_render::common::primitives::PolygonPoints2;
+use sw_render::common::space::ScreenPoint;
+
+const COLORS: [Srgb<u8>; 5] = [
+    Srgb::new(0, 0, 0),
+    Srgb::new(255, 255, 255),
+    Srgb::new(255, 0, 0),
+    Srgb::new(0, 255, 0),
+    Srgb::new(0, 0, 255),
+];
+
+fn round_trip<F: PixelFormat>(color: Srgb<u8>) -> Srgb<u8> {
+    F::unpack(F::pack(color))
+}
+
+#[test]
+fn packs_known_values() {
+    assert_eq!(Xrgb8888::pack(Srgb::new(0x12, 0x34, 0x56)), 0x123456);
+    assert_eq!(Rgba8888::pack(Srgb::new(0x12, 0x34, 0x56)), 0x123456FF);
+    assert_eq!(Rgb565::pack(Srgb::new(255, 0, 0)), 0xF800);
+    assert_eq!(Rgb565::pack(Srgb::new(0, 255, 0)), 0x07E0);
+    assert_eq!(Rgb565::pack(Srgb::new(0, 0, 255)), 0x001F);
+    assert_eq!(L8::pack(Srgb::new(255, 255, 255)), 255);
+    assert_eq!(L8::pack(Srgb::new(0, 255, 0)), 182);
+}
+
+#[test]
+fn saturated_colors_survive_a_round_trip() {
+    for color in COLORS {
+        assert_eq!(round_trip::<Xrgb8888>(color), color);
+        assert_eq!(round_trip::<Rgba8888>(color), color);
+        assert_eq!(round_trip::<Rgb565>(color), color);
+    }
+}
+
+#[test]
+fn rgb565_loses_at_most_the_dropped_bits() {
+    for value in 0..=u8::MAX {
+        let unpacked = round_trip::<Rgb565>(Srgb::new(value, value, value));
+        assert!(unpacked.red.abs_diff(value) < 8);
+        assert!(unpacked.green.abs_diff(value) < 4);
+        assert!(unpacked.blue.abs_diff(value) < 8);
+    }
+}
+
+#[test]
+fn renders_into_native_rgb565() {
+    let dimensions = Vector2::new(16, 16);
+    let triangle = PolygonPoints2::new([
+        ScreenPoint::new(1.0, 1.0),
+        ScreenPoint::new(15.0, 3.0),
+        ScreenPoint::new(4.0, 14.0),
+    ]);
+    let color = Srgb::new(200, 100, 50);
+
+    let mut image = Image::new(dimensions);
+    image.frame_buffer().fill_triangle(&triangle, color);
+
+    let mut data = vec![0u16; (dimensions.x * dimensions.y) as usize];
+    let mut frame_buffer = FrameBuffer::<_, Rgb565>::new(&mut data, dimensions);
+    frame_buffer.fill_triangle(&triangle, color);
+
+    for y in 0..dimensions.y {
+        for x in 0..dimensions.x {
+            let expected = round_trip::<Rgb565>(image.get_pixel(x, y).unwrap());
+            assert_eq!(frame_buffer.get_pixel(x, y), Some(expected));
+        }
+    }
+}
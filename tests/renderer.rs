@@ -0,0 +1,306 @@
+use glamour::Vector2;
+use palette::{LinSrgb, Srgb};
+use std::io::Cursor;
+use sw_render::buffers::depth::DepthBuffer;
+use sw_render::buffers::image::Image;
+use sw_render::common::camera::{Camera, OrthographicCamera, PerspectiveCamera};
+use sw_render::common::space::{
+    to_homogeneous_clip_point, HomogeneousClipPoint, ModelBox, ModelPoint, ModelToWorldTransform,
+    TexturePoint, WorldPoint, WorldVector,
+};
+use sw_render::objects::mesh::{Mesh, MeshVertex};
+use sw_render::pipeline::renderer::Renderer;
+use sw_render::shaders::traits::{SceneUniforms, Shader};
+use sw_render::shaders::vertex::ClipVertex;
+use sw_render::shaders::vertex_post_processing::{CullingShader, FaceCulling, Winding};
+
+// A unit square facing +Z, centered on the origin
+const QUAD_OBJ: &str = "\
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+f 1 2 3 4
+";
+
+// Paints every covered pixel white
+struct FlatShader;
+
+impl Shader for FlatShader {
+    type Attributes = MeshVertex;
+    type Uniforms = ();
+    type Varyings = ();
+
+    fn vertex(&self, attributes: &MeshVertex, _: &(), scene: &SceneUniforms) -> ClipVertex<()> {
+        ClipVertex {
+            position: to_homogeneous_clip_point(&scene.model_to_clip, attributes.position),
+            varyings: (),
+        }
+    }
+
+    fn fragment(&self, _: &(), _: &(), _: &SceneUniforms) -> Option<LinSrgb> {
+        Some(LinSrgb::new(1.0, 1.0, 1.0))
+    }
+}
+
+// Two units high and wide, so the quad covers the middle half of the view
+fn camera() -> OrthographicCamera {
+    OrthographicCamera::new(
+        WorldPoint::new(0.0, 0.0, 5.0),
+        WorldVector::new(0.0, 0.0, -1.0),
+        1.0,
+        10.0,
+        2.0,
+        1.0,
+    )
+}
+
+fn render<S: Shader<Attributes = MeshVertex>>(
+    renderer: &Renderer,
+    camera: &dyn Camera,
+    dimensions: Vector2<u32>,
+    mesh: &Mesh,
+    shader: &S,
+    uniforms: &S::Uniforms,
+) -> Image {
+    let mut image = Image::new(dimensions);
+    let mut depth_buffer = DepthBuffer::new(dimensions);
+    let mut frame_buffer = image.frame_buffer();
+    renderer
+        .begin_pass(&mut frame_buffer, &mut depth_buffer, camera, &[])
+        .draw(mesh, &ModelToWorldTransform::IDENTITY, shader, uniforms);
+
+    image
+}
+
+// Inclusive bounds of the lit pixels, min x, min y, max x and max y
+fn covered_rect(image: &Image) -> Option<[u32; 4]> {
+    let dimensions = image.dimensions();
+    let mut rect: Option<[u32; 4]> = None;
+    for y in 0..dimensions.y {
+        for x in 0..dimensions.x {
+            if image.get_pixel(x, y).unwrap().red == 0 {
+                continue;
+            }
+            rect = Some(rect.map_or([x, y, x, y], |[min_x, min_y, max_x, max_y]| {
+                [min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)]
+            }));
+        }
+    }
+
+    rect
+}
+
+#[test]
+fn passes_map_onto_the_whole_frame_buffer() {
+    let quad = Mesh::from_obj(Cursor::new(QUAD_OBJ)).unwrap();
+    let renderer = Renderer::new();
+
+    // One renderer, any target size
+    let small = render(
+        &renderer,
+        &camera(),
+        Vector2::new(8, 8),
+        &quad,
+        &FlatShader,
+        &(),
+    );
+    assert_eq!(covered_rect(&small), Some([2, 2, 5, 5]));
+    let large = render(
+        &renderer,
+        &camera(),
+        Vector2::new(32, 32),
+        &quad,
+        &FlatShader,
+        &(),
+    );
+    assert_eq!(covered_rect(&large), Some([8, 8, 23, 23]));
+}
+
+// Counter-clockwise on screen on the left, clockwise on the right
+fn wound_triangles() -> Mesh {
+    let vertices = [
+        (-0.9, -0.5),
+        (-0.1, -0.5),
+        (-0.5, 0.5),
+        (0.1, -0.5),
+        (0.5, 0.5),
+        (0.9, -0.5),
+    ]
+    .map(|(x, y)| MeshVertex {
+        position: ModelPoint::new(x, y, 0.0),
+        ..Default::default()
+    });
+
+    Mesh::new(
+        vertices.to_vec(),
+        vec![[0, 1, 2], [3, 4, 5]],
+        ModelBox::new(
+            ModelPoint::new(-0.9, -0.5, 0.0),
+            ModelPoint::new(0.9, 0.5, 0.0),
+        ),
+        Vec::new(),
+    )
+}
+
+#[test]
+fn faces_are_culled_by_winding() {
+    let mesh = wound_triangles();
+    let mut renderer = Renderer::new();
+    let dimensions = Vector2::new(16, 16);
+
+    // Whether the left and the right triangle are drawn
+    let cases = [
+        (FaceCulling::Back, Winding::CounterClockwise, [true, false]),
+        (FaceCulling::Back, Winding::Clockwise, [false, true]),
+        (FaceCulling::Front, Winding::CounterClockwise, [false, true]),
+        (FaceCulling::Front, Winding::Clockwise, [true, false]),
+        (FaceCulling::None, Winding::CounterClockwise, [true, true]),
+        (FaceCulling::None, Winding::Clockwise, [true, true]),
+    ];
+    for (face_culling, winding, expected) in cases {
+        *renderer.culling_shader_mut() = CullingShader::new(face_culling, winding);
+        let image = render(&renderer, &camera(), dimensions, &mesh, &FlatShader, &());
+
+        let is_drawn = [0..8, 8..16].map(|columns| {
+            columns
+                .into_iter()
+                .any(|x| (0..dimensions.y).any(|y| image.get_pixel(x, y).unwrap().red != 0))
+        });
+        assert_eq!(is_drawn, expected, "{face_culling:?}, {winding:?}");
+    }
+}
+
+#[test]
+fn triangles_outside_one_plane_are_culled() {
+    let triangle = |points: [(f32, f32, f32); 3]| {
+        points.map(|(x, y, z)| ClipVertex {
+            position: HomogeneousClipPoint::new(x, y, z, 1.0),
+            varyings: (),
+        })
+    };
+    let culling_shader = CullingShader::new(FaceCulling::None, Winding::CounterClockwise);
+
+    // Every vertex right of the right plane, or beyond the far plane
+    assert!(culling_shader.cull(&triangle([
+        (1.5, 0.0, 0.0),
+        (3.0, 0.0, 0.0),
+        (2.0, 1.0, 0.0)
+    ])));
+    assert!(culling_shader.cull(&triangle([
+        (0.0, 0.0, 1.5),
+        (0.5, 0.0, 1.2),
+        (0.0, 0.5, 2.0)
+    ])));
+
+    // Straddling a plane, or outside of different planes, is left to clipping
+    assert!(!culling_shader.cull(&triangle([
+        (0.5, 0.0, 0.0),
+        (3.0, 0.0, 0.0),
+        (2.0, 1.0, 0.0)
+    ])));
+    assert!(!culling_shader.cull(&triangle([
+        (1.6, 0.6, 0.0),
+        (3.0, 3.0, 0.0),
+        (0.6, 1.6, 0.0)
+    ])));
+}
+
+// Shows the interpolated V texture coordinate in the red channel
+struct TextureCoordinateShader;
+
+impl Shader for TextureCoordinateShader {
+    type Attributes = MeshVertex;
+    type Uniforms = ();
+    type Varyings = TexturePoint;
+
+    fn vertex(
+        &self,
+        attributes: &MeshVertex,
+        _: &(),
+        scene: &SceneUniforms,
+    ) -> ClipVertex<TexturePoint> {
+        ClipVertex {
+            position: to_homogeneous_clip_point(&scene.model_to_clip, attributes.position),
+            varyings: attributes.texture_coordinates,
+        }
+    }
+
+    fn fragment(&self, varyings: &TexturePoint, _: &(), _: &SceneUniforms) -> Option<LinSrgb> {
+        Some(LinSrgb::new(varyings.y, 0.0, 0.0))
+    }
+}
+
+#[test]
+fn varyings_are_perspective_correct() {
+    // A floor one unit below the camera, from 2 to 10 units ahead, V going from 0 to 1
+    let (near, far) = (2.0, 10.0);
+    let vertices = [
+        (-4.0, near, 0.0),
+        (4.0, near, 0.0),
+        (4.0, far, 1.0),
+        (-4.0, far, 1.0),
+    ]
+    .map(|(x, distance, v)| MeshVertex {
+        position: ModelPoint::new(x, -1.0, -distance),
+        texture_coordinates: TexturePoint::new(0.5, v),
+        ..Default::default()
+    });
+    let floor = Mesh::new(
+        vertices.to_vec(),
+        vec![[0, 1, 2], [0, 2, 3]],
+        ModelBox::new(
+            ModelPoint::new(-4.0, -1.0, -far),
+            ModelPoint::new(4.0, -1.0, -near),
+        ),
+        Vec::new(),
+    );
+    let camera = PerspectiveCamera::new(
+        WorldPoint::ZERO,
+        WorldVector::new(0.0, 0.0, -1.0),
+        0.5,
+        20.0,
+        90.0,
+        1.0,
+    );
+
+    let height = 64;
+    let image = render(
+        &Renderer::new(),
+        &camera,
+        Vector2::new(height, height),
+        &floor,
+        &TextureCoordinateShader,
+        &(),
+    );
+
+    // With a 90° field of view, the floor is seen at NDC height -1 / distance
+    let ndc_y = |row: u32| 1.0 - 2.0 * (row as f32 + 0.5) / height as f32;
+    let (near_ndc_y, far_ndc_y) = (-1.0 / near, -1.0 / far);
+    let mut checked_rows = 0;
+    for row in 0..height {
+        let y = ndc_y(row);
+        if !(near_ndc_y < y && y < far_ndc_y) {
+            continue;
+        }
+
+        let pixel = image.get_pixel(height / 2, row).unwrap();
+        let sampled = Srgb::new(pixel.red, 0, 0)
+            .into_format::<f32>()
+            .into_linear::<f32>()
+            .red;
+        let expected = (-1.0 / y - near) / (far - near);
+        let affine = (y - near_ndc_y) / (far_ndc_y - near_ndc_y);
+
+        assert!(
+            (sampled - expected).abs() < 0.01,
+            "row {row}: expected {expected}, got {sampled}"
+        );
+        // Halfway up the floor, interpolating linearly in screen space would be far off
+        if (0.25..0.75).contains(&affine) {
+            assert!((affine - expected).abs() > 0.1, "row {row}");
+        }
+        checked_rows += 1;
+    }
+    assert!(checked_rows > 10);
+}
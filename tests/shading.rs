@@ -0,0 +1,62 @@
+use glamour::Angle;
+use std::f32::consts::{PI, TAU};
+use sw_render::common::space::{
+    to_normal_transform, ModelPoint, ModelToClipTransform, ModelVector, TexturePoint, WorldPoint,
+    WorldVector,
+};
+use sw_render::objects::mesh::MeshVertex;
+use sw_render::objects::object::Object;
+use sw_render::shaders::shading::{Material, PhongShading};
+use sw_render::shaders::traits::{SceneUniforms, Shader};
+
+#[test]
+fn normals_stay_perpendicular_to_non_uniformly_scaled_surfaces() {
+    let mut object = Object::empty();
+    object.set_scale(WorldVector::new(3.0, 1.0, 0.5));
+    object.rotate(WorldVector::Y, Angle::from_degrees(30.0));
+    object.set_translation(WorldVector::new(1.0, 2.0, 3.0));
+    object.update_world_transforms();
+    let model_to_world = object.world_transform();
+    let scene = SceneUniforms {
+        model_to_world,
+        normal_to_world: to_normal_transform(&model_to_world),
+        model_to_clip: ModelToClipTransform::IDENTITY,
+        camera_position: WorldPoint::ZERO,
+        lights: &[],
+    };
+
+    // A unit sphere, whose normals are its positions, turned into a squashed ellipsoid
+    for latitude_step in 1..8 {
+        for longitude_step in 0..16 {
+            let polar = PI * latitude_step as f32 / 8.0;
+            let azimuth = TAU * longitude_step as f32 / 16.0;
+            let (polar_sin, polar_cos) = polar.sin_cos();
+            let (azimuth_sin, azimuth_cos) = azimuth.sin_cos();
+            let normal =
+                ModelVector::new(polar_sin * azimuth_cos, polar_cos, polar_sin * azimuth_sin);
+            let vertex = MeshVertex {
+                position: ModelPoint::from_vector(normal),
+                normal,
+                texture_coordinates: TexturePoint::ZERO,
+            };
+
+            let (_, world_normal, _) = PhongShading::default()
+                .vertex(&vertex, &Material::default(), &scene)
+                .varyings;
+            assert!((world_normal.length() - 1.0).abs() < 1e-5);
+
+            // Both directions along the surface, carried into world space like positions
+            let tangents = [
+                ModelVector::new(polar_cos * azimuth_cos, -polar_sin, polar_cos * azimuth_sin),
+                ModelVector::new(-azimuth_sin, 0.0, azimuth_cos),
+            ];
+            for tangent in tangents {
+                let world_tangent = model_to_world.map_vector(tangent).normalize();
+                assert!(
+                    world_normal.dot(world_tangent).abs() < 1e-5,
+                    "{world_normal:?} is not perpendicular to {world_tangent:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use glamour::Vector2;
+use palette::LinSrgb;
+use std::io::Cursor;
+use std::rc::Rc;
+use sw_render::common::space::{WorldPoint, WorldVector};
+use sw_render::lights::directional_light::DirectionalLight;
+use sw_render::lights::shadow_map::{ShadowFilter, ShadowMap};
+use sw_render::objects::mesh::Mesh;
+use sw_render::objects::object::Object;
+use sw_render::pipeline::renderer::Renderer;
+
+// An 8 by 8 ground plane at y = 0
+const GROUND_OBJ: &str = "\
+v -4 0 -4
+v 4 0 -4
+v 4 0 4
+v -4 0 4
+f 1 4 3 2
+";
+
+// A 2 by 2 square hovering one unit above the middle of the ground
+const OCCLUDER_OBJ: &str = "\
+v -1 1 -1
+v 1 1 -1
+v 1 1 1
+v -1 1 1
+f 1 4 3 2
+";
+
+fn object(obj: &str) -> Object {
+    let mut object = Object::new(Rc::new(Mesh::from_obj(Cursor::new(obj)).unwrap()));
+    object.update_world_transforms();
+    object
+}
+
+// Slanted so that the ground's depth changes across every texel, the occluder's shadow lands
+// half a unit towards +X, covering -0.5 <= x <= 1.5 and -1 <= z <= 1
+fn light() -> DirectionalLight {
+    DirectionalLight::new(
+        WorldVector::new(1.0, -2.0, 0.0),
+        LinSrgb::new(1.0, 1.0, 1.0),
+        1.0,
+    )
+}
+
+fn render_shadow_map(bias: f32, slope_bias: f32, filter: ShadowFilter) -> ShadowMap {
+    let (ground, occluder) = (object(GROUND_OBJ), object(OCCLUDER_OBJ));
+    let mut shadow_map = ShadowMap::new(Vector2::new(64, 64));
+    shadow_map.bias = bias;
+    shadow_map.slope_bias = slope_bias;
+    shadow_map.filter = filter;
+
+    let renderer = Renderer::new();
+    let mut shadow_pass =
+        renderer.begin_shadow_pass(&mut shadow_map, &light(), &[&ground, &occluder]);
+    shadow_pass.draw_object(&ground);
+    shadow_pass.draw_object(&occluder);
+
+    shadow_map
+}
+
+// Points on the ground on a grid, leaving out the shadow and a margin around it
+fn unoccluded_ground_points() -> impl Iterator<Item = WorldPoint> {
+    (-14..=14)
+        .flat_map(|x| {
+            (-14..=14).map(move |z| WorldPoint::new(x as f32 * 0.25, 0.0, z as f32 * 0.25))
+        })
+        .filter(|point| !((-1.0..=2.0).contains(&point.x) && (-1.5..=1.5).contains(&point.z)))
+}
+
+#[test]
+fn occluders_shadow_what_is_below_them() {
+    let shadow_map = render_shadow_map(0.002, 2.0, ShadowFilter::Hard);
+
+    // In the middle of the shadow
+    assert_eq!(shadow_map.visibility(WorldPoint::new(0.5, 0.0, 0.0)), 0.0);
+    assert_eq!(shadow_map.visibility(WorldPoint::new(1.0, 0.0, 0.5)), 0.0);
+    // Beside it, and right below the occluder but outside its slanted shadow
+    assert_eq!(shadow_map.visibility(WorldPoint::new(-2.5, 0.0, 0.0)), 1.0);
+    assert_eq!(shadow_map.visibility(WorldPoint::new(0.5, 0.0, 2.5)), 1.0);
+    assert_eq!(shadow_map.visibility(WorldPoint::new(-0.8, 0.0, 0.0)), 1.0);
+    // The occluder itself
+    assert_eq!(shadow_map.visibility(WorldPoint::new(0.0, 1.0, 0.0)), 1.0);
+    // Outside of the map
+    assert_eq!(shadow_map.visibility(WorldPoint::new(20.0, 0.0, 0.0)), 1.0);
+}
+
+#[test]
+fn slope_bias_keeps_the_ground_from_shadowing_itself() {
+    let biased = render_shadow_map(0.0, 2.0, ShadowFilter::Hard);
+    for point in unoccluded_ground_points() {
+        assert_eq!(biased.visibility(point), 1.0, "acne at {point:?}");
+    }
+
+    // Without any bias, points between texel centers are compared against the depth the ground
+    // has at the center, which is closer to the light for about half of them
+    let unbiased = render_shadow_map(0.0, 0.0, ShadowFilter::Hard);
+    let acne = unoccluded_ground_points()
+        .filter(|&point| unbiased.visibility(point) < 1.0)
+        .count();
+    assert!(acne > 10, "only {acne} points in acne");
+}
+
+#[test]
+fn percentage_closer_filtering_softens_shadow_edges() {
+    let hard = render_shadow_map(0.002, 2.0, ShadowFilter::Hard);
+    let pcf = render_shadow_map(0.002, 2.0, ShadowFilter::Pcf { radius: 1 });
+
+    // A texel is about 0.15 units wide, so 3 by 3 texels stay inside or outside of the shadow
+    assert_eq!(pcf.visibility(WorldPoint::new(0.5, 0.0, 0.0)), 0.0);
+    for point in unoccluded_ground_points() {
+        assert_eq!(pcf.visibility(point), 1.0, "acne at {point:?}");
+    }
+
+    // Across the shadow's far edge at x = 1.5, only some of the filtered texels are shadowed
+    let edge_visibilities: Vec<f32> = (0..=20)
+        .map(|step| pcf.visibility(WorldPoint::new(1.0 + step as f32 * 0.05, 0.0, 0.0)))
+        .collect();
+    assert!(
+        edge_visibilities
+            .iter()
+            .any(|&visibility| 0.0 < visibility && visibility < 1.0),
+        "{edge_visibilities:?}"
+    );
+    assert!(edge_visibilities.windows(2).all(|pair| pair[0] <= pair[1]));
+    // Hard shadows switch between fully lit and fully shadowed
+    for step in 0..=20 {
+        let visibility = hard.visibility(WorldPoint::new(1.0 + step as f32 * 0.05, 0.0, 0.0));
+        assert!(visibility == 0.0 || visibility == 1.0);
+    }
+}
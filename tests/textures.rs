@@ -0,0 +1,185 @@
+use glamour::Vector2;
+use palette::{LinSrgba, Srgba};
+use std::io::Cursor;
+use sw_render::common::space::TexturePoint;
+use sw_render::textures::sampler::{Filter, Sampler, WrapMode};
+use sw_render::textures::texture::Texture;
+use sw_render::textures::tga::{read_tga, TgaError};
+
+// Red carries the value, which keeps sampled results easy to check
+fn red_texture(dimensions: Vector2<u32>, values: &[f32]) -> Texture {
+    let texels = values
+        .iter()
+        .map(|&value| LinSrgba::new(value, 0.0, 0.0, 1.0))
+        .collect();
+    Texture::new(dimensions, texels).unwrap()
+}
+
+fn sample_red(texture: &Texture, sampler: Sampler, u: f32, v: f32) -> f32 {
+    texture.sample(&sampler, TexturePoint::new(u, v)).red
+}
+
+#[test]
+fn wrap_modes_pick_the_right_texels() {
+    let texture = red_texture(Vector2::new(4, 1), &[0.0, 1.0, 2.0, 3.0]);
+    let sample = |wrap, u| sample_red(&texture, Sampler::new(Filter::Nearest, wrap), u, 0.5);
+
+    // Inside the texture every mode agrees
+    for wrap in [WrapMode::Repeat, WrapMode::Clamp, WrapMode::Mirror] {
+        assert_eq!(sample(wrap, 0.375), 1.0);
+    }
+
+    // Half a texel left of the texture, then one and a half texels right of it
+    assert_eq!(sample(WrapMode::Repeat, -0.125), 3.0);
+    assert_eq!(sample(WrapMode::Clamp, -0.125), 0.0);
+    assert_eq!(sample(WrapMode::Mirror, -0.125), 0.0);
+    assert_eq!(sample(WrapMode::Repeat, 1.375), 1.0);
+    assert_eq!(sample(WrapMode::Clamp, 1.375), 3.0);
+    assert_eq!(sample(WrapMode::Mirror, 1.375), 2.0);
+    // A whole mirrored period later the texture repeats as is
+    assert_eq!(sample(WrapMode::Mirror, 2.375), 1.0);
+
+    // The axes wrap independently
+    let column = red_texture(Vector2::new(1, 2), &[1.0, 0.0]);
+    let sampler = Sampler {
+        filter: Filter::Nearest,
+        wrap_u: WrapMode::Repeat,
+        wrap_v: WrapMode::Clamp,
+    };
+    assert_eq!(sample_red(&column, sampler, 7.5, 1.25), 1.0);
+    assert_eq!(sample_red(&column, sampler, -3.5, -0.25), 0.0);
+}
+
+#[test]
+fn v_points_up_the_texture() {
+    // The first row is the top one
+    let texture = red_texture(Vector2::new(1, 2), &[1.0, 0.0]);
+    let sampler = Sampler::new(Filter::Nearest, WrapMode::Clamp);
+    assert_eq!(sample_red(&texture, sampler, 0.5, 0.75), 1.0);
+    assert_eq!(sample_red(&texture, sampler, 0.5, 0.25), 0.0);
+}
+
+#[test]
+fn bilinear_filtering_weighs_the_four_nearest_texels() {
+    // Top-left, top-right, bottom-left, bottom-right
+    let texture = red_texture(Vector2::new(2, 2), &[0.0, 0.2, 0.4, 0.8]);
+    let clamp = Sampler::new(Filter::Bilinear, WrapMode::Clamp);
+    let assert_sample = |sampler, u, v, expected: f32| {
+        let actual = sample_red(&texture, sampler, u, v);
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {expected} at ({u}, {v}), got {actual}"
+        );
+    };
+
+    // On a texel center only that texel counts
+    assert_sample(clamp, 0.25, 0.75, 0.0);
+    assert_sample(clamp, 0.75, 0.25, 0.8);
+    // Halfway between all four
+    assert_sample(clamp, 0.5, 0.5, 0.35);
+    // A quarter texel right of the left column, halfway down
+    assert_sample(
+        clamp,
+        0.375,
+        0.5,
+        0.5 * (0.75 * 0.0 + 0.25 * 0.2) + 0.5 * (0.75 * 0.4 + 0.25 * 0.8),
+    );
+
+    // Past the left edge, clamping holds the edge texels while repeating blends in the right ones
+    assert_sample(clamp, 0.1, 0.75, 0.0);
+    let repeat = Sampler::new(Filter::Bilinear, WrapMode::Repeat);
+    assert_sample(repeat, 0.1, 0.75, 0.3 * 0.2);
+}
+
+const RED: Srgba<u8> = Srgba::new(255, 0, 0, 255);
+const GREEN: Srgba<u8> = Srgba::new(0, 255, 0, 255);
+const BLUE: Srgba<u8> = Srgba::new(0, 0, 255, 255);
+const WHITE: Srgba<u8> = Srgba::new(255, 255, 255, 255);
+
+fn tga_header(image_type: u8, width: u16, height: u16, pixel_depth: u8, descriptor: u8) -> Vec<u8> {
+    let mut header = vec![0, 0, image_type, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    header.extend_from_slice(&width.to_le_bytes());
+    header.extend_from_slice(&height.to_le_bytes());
+    header.extend_from_slice(&[pixel_depth, descriptor]);
+    header
+}
+
+fn bgr(color: Srgba<u8>) -> [u8; 3] {
+    [color.blue, color.green, color.red]
+}
+
+#[test]
+fn tga_origins_are_flipped_to_top_left() {
+    let expected = [RED, GREEN, BLUE, WHITE];
+
+    // The default origin is the bottom left
+    let mut data = tga_header(2, 2, 2, 24, 0);
+    for color in [BLUE, WHITE, RED, GREEN] {
+        data.extend_from_slice(&bgr(color));
+    }
+    let texture = read_tga(Cursor::new(data)).unwrap();
+    assert_eq!(texture.dimensions(), Vector2::new(2, 2));
+    assert_eq!(texture.texels(), &expected);
+
+    // Top to bottom, right to left
+    let mut data = tga_header(2, 2, 2, 24, 0x30);
+    for color in [GREEN, RED, WHITE, BLUE] {
+        data.extend_from_slice(&bgr(color));
+    }
+    assert_eq!(read_tga(Cursor::new(data)).unwrap().texels(), &expected);
+}
+
+#[test]
+fn tga_rle_packets_are_expanded() {
+    // 32 bits per pixel, top to bottom, with a three byte image ID to skip
+    let mut data = tga_header(10, 3, 2, 32, 0x20);
+    data[0] = 3;
+    data.extend_from_slice(b"id!");
+    // A run of four translucent reds, then two raw pixels
+    data.extend_from_slice(&[0x83, 0, 0, 255, 128]);
+    data.extend_from_slice(&[0x01, 255, 0, 0, 255, 0, 255, 0, 255]);
+
+    let translucent_red = Srgba::new(255, 0, 0, 128);
+    assert_eq!(
+        read_tga(Cursor::new(data)).unwrap().texels(),
+        &[
+            translucent_red,
+            translucent_red,
+            translucent_red,
+            translucent_red,
+            BLUE,
+            GREEN
+        ]
+    );
+
+    // Grayscale, with a run that overshoots the last pixel
+    let mut data = tga_header(11, 2, 1, 8, 0x20);
+    data.extend_from_slice(&[0x82, 100]);
+    let gray = Srgba::new(100, 100, 100, 255);
+    assert_eq!(read_tga(Cursor::new(data)).unwrap().texels(), &[gray, gray]);
+}
+
+#[test]
+fn unsupported_or_truncated_tgas_are_rejected() {
+    // Color-mapped
+    let data = tga_header(1, 1, 1, 8, 0);
+    assert!(matches!(
+        read_tga(Cursor::new(data)),
+        Err(TgaError::UnsupportedImageType(1))
+    ));
+
+    let data = tga_header(2, 1, 1, 16, 0);
+    assert!(matches!(
+        read_tga(Cursor::new(data)),
+        Err(TgaError::UnsupportedPixelDepth(16))
+    ));
+
+    let mut data = tga_header(2, 2, 2, 24, 0);
+    data.extend_from_slice(&bgr(RED));
+    assert!(matches!(read_tga(Cursor::new(data)), Err(TgaError::Io(_))));
+
+    let mut data = tga_header(10, 2, 2, 24, 0);
+    data.extend_from_slice(&[0x81]);
+    data.extend_from_slice(&bgr(RED));
+    assert!(matches!(read_tga(Cursor::new(data)), Err(TgaError::Io(_))));
+}
@@ -0,0 +1,110 @@
+use sw_render::common::space::ModelPoint;
+use sw_render::objects::triangulation::{triangulate, TriangulationError};
+
+fn polygon(points: &[(f32, f32)]) -> Vec<ModelPoint> {
+    points
+        .iter()
+        .map(|&(x, y)| ModelPoint::new(x, y, 0.0))
+        .collect()
+}
+
+fn signed_area(a: ModelPoint, b: ModelPoint, c: ModelPoint) -> f32 {
+    (b - a).cross(c - a).z / 2.0
+}
+
+// Checks that the triangles use every corner, keep the polygon's winding and add up to its area
+fn assert_covers(points: &[ModelPoint], triangles: &[[usize; 3]]) {
+    assert_eq!(triangles.len(), points.len() - 2);
+    for idx in 0..points.len() {
+        assert!(triangles.iter().flatten().any(|&corner| corner == idx));
+    }
+
+    let polygon_area: f32 = (1..points.len() - 1)
+        .map(|idx| signed_area(points[0], points[idx], points[idx + 1]))
+        .sum();
+    let mut triangle_area = 0.0;
+    for &[a, b, c] in triangles {
+        let area = signed_area(points[a], points[b], points[c]);
+        assert!(area >= 0.0, "{:?} is wound the wrong way", [a, b, c]);
+        triangle_area += area;
+    }
+    assert!((triangle_area - polygon_area).abs() < 1e-5);
+}
+
+#[test]
+fn convex_polygons_become_fans() {
+    let points = polygon(&[(0.0, 0.0), (2.0, 0.0), (3.0, 1.0), (1.0, 2.0), (-1.0, 1.0)]);
+    let triangles = triangulate(&points).unwrap();
+    assert_eq!(triangles, vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]]);
+}
+
+#[test]
+fn concave_polygons_are_ear_clipped() {
+    // An arrowhead pointing up, notched at (2, 1)
+    let points = polygon(&[(0.0, 0.0), (2.0, 1.0), (4.0, 0.0), (2.0, 3.0)]);
+    let triangles = triangulate(&points).unwrap();
+    assert_covers(&points, &triangles);
+
+    // Clockwise, and in a plane other than XY
+    let points: Vec<ModelPoint> = [
+        (0.0, 0.0),
+        (0.0, 2.0),
+        (1.0, 2.0),
+        (1.0, 1.0),
+        (2.0, 1.0),
+        (2.0, 0.0),
+    ]
+    .iter()
+    .map(|&(y, z)| ModelPoint::new(0.5, y, z))
+    .collect();
+    let triangles = triangulate(&points).unwrap();
+    assert_eq!(triangles.len(), 4);
+    let normal = (points[1] - points[0])
+        .cross(points[2] - points[0])
+        .normalize();
+    for &[a, b, c] in &triangles {
+        let triangle_normal = (points[b] - points[a]).cross(points[c] - points[a]);
+        assert!(triangle_normal.dot(normal) > 0.0);
+    }
+}
+
+#[test]
+fn collinear_corners_are_kept() {
+    // A square with a point halfway along its bottom edge
+    let points = polygon(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+    assert_covers(&points, &triangulate(&points).unwrap());
+
+    // An L shape with every unit point along its edges
+    let points = polygon(&[
+        (0.0, 0.0),
+        (1.0, 0.0),
+        (2.0, 0.0),
+        (2.0, 1.0),
+        (1.0, 1.0),
+        (1.0, 2.0),
+        (1.0, 3.0),
+        (0.0, 3.0),
+        (0.0, 2.0),
+        (0.0, 1.0),
+    ]);
+    assert_covers(&points, &triangulate(&points).unwrap());
+}
+
+#[test]
+fn repeated_corners_do_not_block_ear_clipping() {
+    // The arrowhead again, with its notch given twice. Every proper ear touches the notch.
+    let points = polygon(&[(0.0, 0.0), (2.0, 1.0), (2.0, 1.0), (4.0, 0.0), (2.0, 3.0)]);
+    assert_covers(&points, &triangulate(&points).unwrap());
+}
+
+#[test]
+fn degenerate_polygons_are_rejected() {
+    assert_eq!(
+        triangulate(&polygon(&[(0.0, 0.0), (1.0, 0.0)])),
+        Err(TriangulationError::TooFewVertices)
+    );
+    assert_eq!(
+        triangulate(&polygon(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)])),
+        Err(TriangulationError::Degenerate)
+    );
+}
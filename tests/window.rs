@@ -0,0 +1,42 @@
+use glamour::{Point2, Rect, Size2, Vector2};
+use sw_render::present::window::letterbox_rect;
+
+fn rect(x: u32, y: u32, width: u32, height: u32) -> Option<Rect<u32>> {
+    Some(Rect::new(Point2::new(x, y), Size2::new(width, height)))
+}
+
+fn letterbox(source: (u32, u32), target: (u32, u32)) -> Option<Rect<u32>> {
+    letterbox_rect(
+        Vector2::new(source.0, source.1),
+        Vector2::new(target.0, target.1),
+    )
+}
+
+#[test]
+fn letterbox_keeps_the_aspect_ratio_centered() {
+    // Same aspect ratio, scaled up and down
+    assert_eq!(letterbox((320, 240), (640, 480)), rect(0, 0, 640, 480));
+    assert_eq!(letterbox((320, 240), (160, 120)), rect(0, 0, 160, 120));
+    // Pillarbox in a wider target, letterbox in a taller one
+    assert_eq!(letterbox((320, 240), (800, 480)), rect(80, 0, 640, 480));
+    assert_eq!(letterbox((320, 240), (640, 600)), rect(0, 60, 640, 480));
+}
+
+#[test]
+fn letterbox_rounds_odd_sizes_down() {
+    // An odd bar pixel goes to the right or the bottom
+    assert_eq!(letterbox((4, 3), (9, 6)), rect(0, 0, 8, 6));
+    assert_eq!(letterbox((4, 3), (8, 7)), rect(0, 0, 8, 6));
+    assert_eq!(letterbox((2, 2), (5, 2)), rect(1, 0, 2, 2));
+    // 4 * 5 / 3 is 6.67 pixels wide, rounded down
+    assert_eq!(letterbox((4, 3), (10, 5)), rect(2, 0, 6, 5));
+    // Exactly 31 rows, which scaling by 31 / 7 in floating point would round down to 30
+    assert_eq!(letterbox((2, 7), (9, 31)), rect(0, 0, 8, 31));
+    // Never thinner than a pixel
+    assert_eq!(letterbox((1000, 1), (10, 10)), rect(0, 4, 10, 1));
+    assert_eq!(letterbox((1, 1000), (10, 10)), rect(4, 0, 1, 10));
+
+    // Nothing to show, or nowhere to show it
+    assert_eq!(letterbox((0, 3), (10, 10)), None);
+    assert_eq!(letterbox((4, 3), (10, 0)), None);
+}